@@ -0,0 +1,124 @@
+//! alpha-beta search for ataxx.
+//! a move can take up to eight pieces, so the piece count swings a lot from ply to ply.
+//! the evaluator only adds a small bonus for mobility on top of it.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::ataxx::*;
+use crate::rules::othello::Piece;
+
+/// ataxx as a `Game`. a pass is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub board: AtaxxBoard,
+    pub playing: Piece,
+}
+impl Game for State {
+    type Move = Option<AtaxxMove>;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.playing
+    }
+    fn moves(&self) -> Vec<Option<AtaxxMove>> {
+        if self.board.is_over() {
+            return vec![];
+        }
+        let possible = self.board.moves(self.playing);
+        if possible.is_empty() {
+            vec![None]
+        } else {
+            possible.into_iter().map(Some).collect()
+        }
+    }
+    fn apply(&self, play: Option<AtaxxMove>) -> Self {
+        let board = match play {
+            Some(play) => self.board.clone().play(play, self.playing).unwrap(),
+            None => self.board.clone(),
+        };
+        State {
+            board,
+            playing: self.playing.flip(),
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        self.board.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.board.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+fn eval(state: &State, playing: Piece) -> isize {
+    let board = &state.board;
+    let base_score = board.score(playing) as isize - board.score(playing.flip()) as isize;
+    // clones grow the piece count for good, so they are what mobility is measured in
+    let clones = |piece| board.moves(piece).iter().filter(|m| m.is_clone()).count() as isize;
+    base_score * 10 + clones(playing) - clones(playing.flip())
+}
+
+/// returns best move for `ai_player`, searching `depth` plies ahead through the `width` most promising moves of each.
+/// will return None if `ai_player` has to pass or the game is over.
+/// ```rust
+/// use boardgame_ai::ai::ataxx as ai;
+/// use boardgame_ai::rules::ataxx::*;
+/// use boardgame_ai::rules::othello::{Piece, Point};
+/// let board = AtaxxBoard::decode("
+/// b....
+/// ..w..
+/// .ww..
+/// .....
+/// .....
+/// ", 5, 5).unwrap();
+/// // cloning into the middle of white takes all three
+/// let play = ai::predict(&board, Piece::Black, 1, usize::MAX).unwrap();
+/// assert_eq!(play.to, Point::new(1, 1));
+/// ```
+pub fn predict(
+    board: &AtaxxBoard,
+    ai_player: Piece,
+    depth: usize,
+    width: usize,
+) -> Option<AtaxxMove> {
+    let state = State {
+        board: board.clone(),
+        playing: ai_player,
+    };
+    search::alpha_beta(&state, depth, width, &eval).and_then(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::othello::Point;
+    #[test]
+    fn prefers_clone_over_jump() {
+        // both take the white piece, but the clone keeps the black piece in the corner
+        let input = "
+            b....
+            .....
+            ..w..
+            .....
+            .....
+        ";
+        let board = AtaxxBoard::decode(input, 5, 5).unwrap();
+        let play = predict(&board, Piece::Black, 1, usize::MAX).unwrap();
+        assert!(play.is_clone(), "{:?}", play);
+        assert_eq!(play.to, Point::new(1, 1));
+    }
+    #[test]
+    fn passes_when_walled_in() {
+        let input = "
+            w##...
+            ###...
+            ###...
+            .....b
+        ";
+        let board = AtaxxBoard::decode(input, 6, 4).unwrap();
+        assert_eq!(predict(&board, Piece::White, 3, 8), None);
+        assert!(predict(&board, Piece::Black, 3, 8).is_some());
+    }
+}
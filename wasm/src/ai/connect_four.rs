@@ -0,0 +1,294 @@
+//! connect four AI.
+//!
+//! first it tries to solve the position outright: negamax over the exact result with alpha-beta,
+//! a transposition table, centre-first move ordering and immediate win / forced block detection.
+//! scores follow the usual convention: winning with your k-th last disc is worth k, so faster wins score higher.
+//! if the position is too big to solve within the node budget, it falls back to a depth limited
+//! `search::alpha_beta` over a heuristic that counts open lines.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::connect_four::*;
+use std::collections::HashMap;
+
+impl Game for ConnectFourBoard {
+    type Move = usize;
+    type Player = Disc;
+
+    fn side_to_move(&self) -> Disc {
+        self.turn()
+    }
+    fn moves(&self) -> Vec<usize> {
+        if self.winner().is_some() {
+            return vec![];
+        }
+        self.placeable()
+    }
+    fn apply(&self, column: usize) -> Self {
+        self.clone().place(column, self.turn()).unwrap()
+    }
+    fn is_terminal(&self) -> bool {
+        self.is_over()
+    }
+    fn result(&self, player: Disc) -> Outcome {
+        match self.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+struct Solver {
+    // upper bounds of exact scores, by position
+    table: HashMap<(u128, u128), isize>,
+    nodes: usize,
+    node_limit: usize,
+    order: Vec<usize>,
+}
+impl Solver {
+    fn new(board: &ConnectFourBoard, node_limit: usize) -> Self {
+        // centre columns take part in more lines, so they are tried first
+        let mut order: Vec<usize> = (0..board.width).collect();
+        let center = (board.width as isize - 1) as f64 / 2.0;
+        order.sort_by(|&a, &b| {
+            (a as f64 - center)
+                .abs()
+                .total_cmp(&(b as f64 - center).abs())
+        });
+        Solver {
+            table: HashMap::new(),
+            nodes: 0,
+            node_limit,
+            order,
+        }
+    }
+    fn max_moves(board: &ConnectFourBoard) -> isize {
+        (board.width * board.height) as isize
+    }
+    fn wins_immediately(board: &ConnectFourBoard, column: usize) -> bool {
+        let bit = board.landing_bit(column);
+        bit != 0 && board.has_line(board.discs(board.turn()) | bit)
+    }
+    // exact score for the player to move, or None if the budget ran out.
+    // the board must not be won by anyone yet.
+    fn negamax(
+        &mut self,
+        board: &ConnectFourBoard,
+        mut alpha: isize,
+        mut beta: isize,
+    ) -> Option<isize> {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            return None;
+        }
+        let played = board.moves_played() as isize;
+        let max_moves = Self::max_moves(board);
+        if played == max_moves {
+            return Some(0); // draw
+        }
+        let playable = board.placeable();
+        if playable
+            .iter()
+            .any(|&column| Self::wins_immediately(board, column))
+        {
+            return Some((max_moves + 1 - played) / 2);
+        }
+        // if the opponent threatens to win somewhere, we have to block there
+        let threats: Vec<_> = playable
+            .iter()
+            .copied()
+            .filter(|&column| {
+                let bit = board.landing_bit(column);
+                board.has_line(board.discs(board.turn().flip()) | bit)
+            })
+            .collect();
+        if threats.len() > 1 {
+            return Some(-(max_moves - played) / 2); // can't block both
+        }
+        let mut max = (max_moves - 1 - played) / 2;
+        let key = (board.discs(board.turn()), board.mask());
+        if let Some(&upper) = self.table.get(&key) {
+            max = max.min(upper);
+        }
+        if beta > max {
+            beta = max;
+            if alpha >= beta {
+                return Some(beta);
+            }
+        }
+        let candidates: Vec<usize> = if threats.len() == 1 {
+            threats
+        } else {
+            self.order
+                .iter()
+                .copied()
+                .filter(|&column| board.can_place(column))
+                .collect()
+        };
+        for column in candidates {
+            let next = board.clone().place(column, board.turn()).unwrap();
+            let score = -self.negamax(&next, -beta, -alpha)?;
+            if score >= beta {
+                return Some(score);
+            }
+            alpha = alpha.max(score);
+        }
+        self.table.insert(key, alpha);
+        Some(alpha)
+    }
+}
+
+/// exact score of `board` for the player to move, if it can be found within `node_limit` nodes.
+/// positive means a win, negative a loss and 0 a draw, with perfect play from both sides.
+/// ```rust
+/// use boardgame_ai::ai::connect_four as ai;
+/// use boardgame_ai::rules::connect_four::*;
+/// let board = ConnectFourBoard::decode("
+/// .....
+/// .....
+/// .....
+/// .yy..
+/// .rr..
+/// ", 5, 5, 3).unwrap();
+/// // red wins with its next disc, which is its 3rd last
+/// assert_eq!(ai::solve(&board, 100_000), Some(11));
+/// ```
+pub fn solve(board: &ConnectFourBoard, node_limit: usize) -> Option<isize> {
+    if board.is_over() {
+        return board.winner().is_none().then_some(0);
+    }
+    let mut solver = Solver::new(board, node_limit);
+    let max = (Solver::max_moves(board) + 1) / 2;
+    // the search window is narrowed down with null windows, which prunes a lot more than a full one
+    let (mut min, mut max) = (-max, max);
+    while min < max {
+        let mut med = min + (max - min) / 2;
+        if med <= 0 && min / 2 < med {
+            med = min / 2;
+        } else if med >= 0 && max / 2 > med {
+            med = max / 2;
+        }
+        let score = solver.negamax(board, med, med + 1)?;
+        if score <= med {
+            max = score;
+        } else {
+            min = score;
+        }
+    }
+    Some(min)
+}
+
+fn eval(board: &ConnectFourBoard, playing: Disc) -> isize {
+    let mine = board.discs(playing);
+    let theirs = board.discs(playing.flip());
+    let (width, height, connect) = (
+        board.width as isize,
+        board.height as isize,
+        board.connect as isize,
+    );
+    let mut score = 0;
+    // every window of `connect` cells in every direction
+    for (dx, dy) in [(1, 0), (0, 1), (1, 1), (1, -1)] {
+        for x in 0..width {
+            for y in 0..height {
+                let (end_x, end_y) = (x + dx * (connect - 1), y + dy * (connect - 1));
+                if !(0..width).contains(&end_x) || !(0..height).contains(&end_y) {
+                    continue;
+                }
+                let (mut own, mut other) = (0, 0);
+                for i in 0..connect {
+                    let bit = 1u128 << ((x + dx * i) * (height + 1) + y + dy * i);
+                    own += (mine & bit != 0) as isize;
+                    other += (theirs & bit != 0) as isize;
+                }
+                // a window is only worth something while only one player is in it
+                if other == 0 {
+                    score += own * own;
+                } else if own == 0 {
+                    score -= other * other;
+                }
+            }
+        }
+    }
+    score
+}
+
+/// returns best column to drop into for `ai_player`. None if it's not `ai_player`'s turn or the game is over.
+/// the larger `strength` is, the harder it tries to solve the position before falling back to a heuristic.
+/// `strength` is taken as 1..=10, as othello's `Searcher::new` does.
+/// ```rust
+/// use boardgame_ai::ai::connect_four as ai;
+/// use boardgame_ai::rules::connect_four::*;
+/// let board = ConnectFourBoard::decode("
+/// .......
+/// .......
+/// .......
+/// .......
+/// .yy....
+/// rrr....
+/// ", 7, 6, 4).unwrap();
+/// // yellow has to block red's three
+/// assert_eq!(ai::predict(&board, Disc::Yellow, 1), Some(3));
+/// ```
+pub fn predict(board: &ConnectFourBoard, ai_player: Disc, strength: usize) -> Option<usize> {
+    if board.is_over() || board.turn() != ai_player {
+        return None;
+    }
+    let strength = strength.clamp(1, 10);
+    let node_limit = 1000 << strength;
+    let mut best: Option<(usize, isize)> = None;
+    for column in Solver::new(board, node_limit).order {
+        if !board.can_place(column) {
+            continue;
+        }
+        if Solver::wins_immediately(board, column) {
+            return Some(column);
+        }
+        let next = board.clone().place(column, ai_player).unwrap();
+        let Some(score) = solve(&next, node_limit).map(|score| -score) else {
+            // too big to solve. let the heuristic decide
+            let depth = 2 + strength;
+            return search::alpha_beta(board, depth, usize::MAX, &eval).map(|(column, _)| column);
+        };
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((column, score));
+        }
+    }
+    best.map(|(column, _)| column)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn solves_small_board() {
+        // 4x4 connect four is a known draw
+        let board = ConnectFourBoard::new(4, 4, 4);
+        assert_eq!(solve(&board, 10_000_000), Some(0));
+        // connect 3 on 4x4 is a first player win
+        let board = ConnectFourBoard::new(4, 4, 3);
+        assert!(solve(&board, 10_000_000).unwrap() > 0);
+    }
+    #[test]
+    fn avoids_losing_setup() {
+        // if red plays column 3, yellow lands on top of it and takes the diagonal
+        let board = "
+            .......
+            .......
+            .......
+            ..y....
+            .yrr...
+            yrry...
+        ";
+        let board = ConnectFourBoard::decode(board, 7, 6, 4).unwrap();
+        assert_eq!(board.turn(), Disc::Red);
+        let next_play = predict(&board, Disc::Red, 8).unwrap();
+        assert_ne!(next_play, 3);
+    }
+    #[test]
+    fn falls_back_on_big_boards() {
+        let board = ConnectFourBoard::new(7, 6, 4);
+        let next_play = predict(&board, Disc::Red, 1).unwrap();
+        assert!(board.can_place(next_play));
+    }
+}
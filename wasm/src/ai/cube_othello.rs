@@ -0,0 +1,140 @@
+//! alpha-beta search for cube othello.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::cube_othello::*;
+use crate::rules::othello::Piece;
+
+/// cube othello as a `Game`. a pass is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub board: CubeBoard,
+    pub playing: Piece,
+}
+impl Game for State {
+    type Move = Option<Point3>;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.playing
+    }
+    fn moves(&self) -> Vec<Option<Point3>> {
+        let possible = self.board.placeable(self.playing);
+        if !possible.is_empty() {
+            possible.into_iter().map(Some).collect()
+        } else if self.board.placeable(self.playing.flip()).is_empty() {
+            vec![] // game over
+        } else {
+            vec![None]
+        }
+    }
+    fn apply(&self, play: Option<Point3>) -> Self {
+        let board = match play {
+            Some(at) => self.board.clone().place(at, self.playing).unwrap(),
+            None => self.board.clone(),
+        };
+        State {
+            board,
+            playing: self.playing.flip(),
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        self.board.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.board.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+// game over is scored by the search, so this only needs to judge positions in the middle of the game.
+fn eval(state: &CubeBoard, playing: Piece) -> isize {
+    let base_score = state.score(playing) as isize - state.score(playing.flip()) as isize;
+    let flexibility_score = (state.placeable(playing).len() as isize
+        - state.placeable(playing.flip()).len() as isize)
+        * 3;
+    base_score + flexibility_score + eval_positional_score(state, playing)
+}
+fn eval_positional_score(state: &CubeBoard, playing: Piece) -> isize {
+    let last = state.size() - 1;
+    state
+        .points()
+        .map(|point| {
+            // how many axes this cell is on the outside of.
+            // 3 is one of the 8 corners of the cube, 2 is an edge and 1 is a face.
+            let outside = [point.x, point.y, point.z]
+                .iter()
+                .filter(|&&c| c == 0 || c == last)
+                .count();
+            let score = match outside {
+                3 => 20,
+                2 => 5,
+                1 => 1,
+                _ => 0,
+            };
+            match state.get(point) {
+                Ok(cell) if cell == playing.into() => score,
+                Ok(cell) if cell == playing.flip().into() => -score,
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+/// returns best point to place for `ai_player`, searching `depth` plies ahead.
+/// a cube has a lot more legal moves than a flat board, so keep `depth` small.
+/// will return None if there were no cells that AI can place.
+/// ```rust
+/// use boardgame_ai::ai::cube_othello as ai;
+/// use boardgame_ai::rules::cube_othello::*;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = CubeBoard::new(4);
+/// let next_play = ai::predict(&board, Piece::Black, 2).unwrap();
+/// assert!(board.placeable(Piece::Black).contains(&next_play));
+/// ```
+pub fn predict(state: &CubeBoard, ai_player: Piece, depth: usize) -> Option<Point3> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(&state.board, playing);
+    search::alpha_beta(&state, depth, usize::MAX, &eval).and_then(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn takes_corner() {
+        let board = "
+            ....
+            ....
+            ....
+            ....
+
+            ....
+            .w..
+            ..b.
+            ....
+
+            ....
+            ....
+            ..b.
+            ....
+
+            ....
+            ....
+            ....
+            ....
+        ";
+        let board = CubeBoard::decode(board, 4).unwrap();
+        // (0, 0, 1) and (0, 0, 0) both capture the same disc, but (0, 0, 0) is a corner of the cube
+        assert!(board
+            .placeable(Piece::Black)
+            .contains(&Point3::new(0, 0, 1)));
+        let next_play = predict(&board, Piece::Black, 1).unwrap();
+        assert_eq!(next_play, Point3::new(0, 0, 0));
+    }
+}
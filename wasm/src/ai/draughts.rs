@@ -0,0 +1,119 @@
+//! alpha-beta search for draughts.
+//! captures are mandatory, so the game tree is narrow whenever pieces touch, and material decides most games.
+//! the evaluator counts material, with kings worth more in international draughts where they fly,
+//! and adds a small bonus for men getting closer to being crowned.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::draughts::*;
+use crate::rules::othello::{Piece, Point};
+
+impl Game for DraughtsBoard {
+    type Move = DraughtsMove;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.turn()
+    }
+    fn moves(&self) -> Vec<DraughtsMove> {
+        if self.is_draw() {
+            return vec![];
+        }
+        DraughtsBoard::moves(self)
+    }
+    fn apply(&self, play: DraughtsMove) -> Self {
+        self.clone().play(&play).unwrap()
+    }
+    fn is_terminal(&self) -> bool {
+        self.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.winner() {
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+            None => Outcome::Draw,
+        }
+    }
+}
+
+fn eval(board: &DraughtsBoard, playing: Piece) -> isize {
+    let king = match board.variant {
+        DraughtsVariant::English => 160,
+        DraughtsVariant::International => 300,
+    };
+    let size = board.size();
+    let mut score = 0;
+    for y in 0..size {
+        for x in 0..size {
+            let (piece, value) = match board.get(Point::new(x, y)) {
+                Ok(Square::Man(piece)) => {
+                    let advanced = match piece {
+                        Piece::Black => y,
+                        Piece::White => size - 1 - y,
+                    };
+                    (piece, 100 + 2 * advanced as isize)
+                }
+                Ok(Square::King(piece)) => (piece, king),
+                _ => continue,
+            };
+            score += if piece == playing { value } else { -value };
+        }
+    }
+    score
+}
+
+/// returns best move for `ai_player`, searching `depth` plies ahead.
+/// None if it's not `ai_player`'s turn or the game is over.
+/// ```rust
+/// use boardgame_ai::ai::draughts as ai;
+/// use boardgame_ai::rules::draughts::*;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = DraughtsBoard::new(DraughtsVariant::English);
+/// assert!(ai::predict(&board, Piece::Black, 4).is_some());
+/// assert_eq!(ai::predict(&board, Piece::White, 4), None);
+/// ```
+pub fn predict(board: &DraughtsBoard, ai_player: Piece, depth: usize) -> Option<DraughtsMove> {
+    if board.turn() != ai_player {
+        return None;
+    }
+    search::alpha_beta(board, depth, usize::MAX, &eval).map(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn does_not_hang_a_piece() {
+        // 10-15 walks into white's capture
+        let input = "
+            ........
+            ........
+            ...b....
+            ........
+            .....w..
+            ........
+            ........
+            ........
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        let play = predict(&board, Piece::Black, 2).unwrap();
+        assert_eq!(play.to_string(), "10-14");
+    }
+    #[test]
+    fn takes_the_bigger_capture() {
+        // both captures are legal in english, but only one takes two pieces
+        let input = "
+            ........
+            ..b.....
+            .w.w....
+            ........
+            .....w..
+            ........
+            ........
+            ......w.
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        assert_eq!(board.moves().len(), 2);
+        let play = predict(&board, Piece::Black, 1).unwrap();
+        assert_eq!(play.capture_count(), 2);
+    }
+}
@@ -0,0 +1,28 @@
+//! what the search algorithms in `ai::search` need to know about a game.
+use std::fmt::Debug;
+
+/// how a finished game ended for one of the players.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// a game state: the board, and whose turn it is.
+/// implementors are cheap-ish to clone, as the search algorithms keep a copy per node.
+pub trait Game: Clone {
+    type Move: Copy + PartialEq + Debug;
+    type Player: Copy + PartialEq + Debug;
+
+    /// the player who plays next.
+    fn side_to_move(&self) -> Self::Player;
+    /// every legal move of `side_to_move`. must not be empty unless `is_terminal`,
+    /// so games where a player can be stuck should return a pass move instead.
+    fn moves(&self) -> Vec<Self::Move>;
+    /// the state after `play`. `play` must be one of `moves`.
+    fn apply(&self, play: Self::Move) -> Self;
+    fn is_terminal(&self) -> bool;
+    /// how the game ended for `player`. only meaningful when `is_terminal`.
+    fn result(&self, player: Self::Player) -> Outcome;
+}
@@ -0,0 +1,359 @@
+//! gomoku AI.
+//!
+//! gomoku is mostly decided by threats: a four has to be blocked right away, and an open three
+//! has to be answered before it becomes an open four. so the AI first runs a threat-space search,
+//! which only looks at those forcing moves and can see a lot deeper than a full-width search.
+//! if there is no forced win, it falls back to `search::alpha_beta` over a line-counting heuristic.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::gomoku::*;
+use crate::rules::othello::{Cell, Piece, Point};
+
+impl Game for GomokuBoard {
+    type Move = Point;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.turn()
+    }
+    fn moves(&self) -> Vec<Point> {
+        if self.winner().is_some() {
+            return vec![];
+        }
+        candidates(self, self.turn())
+    }
+    fn apply(&self, play: Point) -> Self {
+        self.clone().place(play, self.turn()).unwrap()
+    }
+    fn is_terminal(&self) -> bool {
+        self.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+// empty points `next` may place on within two cells of a stone. moves far from every stone are hardly ever good.
+// on an empty board, the centre.
+fn candidates(board: &GomokuBoard, next: Piece) -> Vec<Point> {
+    let near: Vec<_> = board
+        .points()
+        .filter(|&at| board.get(at) == Ok(Cell::Empty))
+        .filter(|&at| {
+            (-2..=2).any(|dy| {
+                (-2..=2).any(|dx| {
+                    board
+                        .step(at, (dx, dy), 1)
+                        .is_some_and(|p| !matches!(board.get(p), Ok(Cell::Empty)))
+                })
+            })
+        })
+        .filter(|at| next == Piece::White || !board.is_forbidden(at))
+        .collect();
+    if near.is_empty() {
+        let center = Point::new(board.size / 2, board.size / 2);
+        if board.get(center) == Ok(Cell::Empty) {
+            return vec![center];
+        }
+        return board.placeable(next);
+    }
+    near
+}
+
+// points on the line through `at` along `dir` where `piece` would make five along that line,
+// as if `extra` were `piece` too.
+fn fives_along(
+    board: &GomokuBoard,
+    at: Point,
+    dir: (isize, isize),
+    piece: Piece,
+    extra: &[Point],
+) -> Vec<Point> {
+    (-5..=5)
+        .filter_map(|k| board.step(at, dir, k))
+        .filter(|&r| board.get(r) == Ok(Cell::Empty) && !extra.contains(&r))
+        .filter(|&r| board.is_five(board.run(r, dir, piece, extra), piece))
+        .collect()
+}
+
+// empty points on the line through `at` along `dir` that would give `piece` an open four,
+// which is two ways to make five at once.
+fn open_four_points(
+    board: &GomokuBoard,
+    at: Point,
+    dir: (isize, isize),
+    piece: Piece,
+) -> Vec<Point> {
+    (-4..=4)
+        .filter_map(|k| board.step(at, dir, k))
+        .filter(|&q| board.get(q) == Ok(Cell::Empty))
+        .filter(|&q| fives_along(board, at, dir, piece, &[q]).len() >= 2)
+        .collect()
+}
+
+// true iff `piece` at `at` would make a four.
+fn makes_four(board: &GomokuBoard, at: Point, piece: Piece) -> bool {
+    board.get(at) == Ok(Cell::Empty)
+        && FOUR_DIRECTIONS
+            .iter()
+            .any(|&dir| !fives_along(board, at, dir, piece, &[at]).is_empty())
+}
+
+/// a move that wins for `attacker` by threats alone: fours, which must be blocked,
+/// and open threes, which must be answered. `depth` is the number of attacker moves,
+/// and at most `node_limit` positions are looked at. `attacker` has to be the one to move.
+/// ```rust
+/// use boardgame_ai::ai::gomoku as ai;
+/// use boardgame_ai::rules::gomoku::*;
+/// use boardgame_ai::rules::othello::{Piece, Point};
+/// let board = GomokuBoard::decode("
+/// b.......b
+/// .........
+/// .........
+/// .........
+/// ...www...
+/// .........
+/// .........
+/// .........
+/// b.......b
+/// ", 9, GomokuRule::FreeStyle).unwrap();
+/// // extending the open three to an open four wins
+/// let play = ai::threat_space_search(&board, Piece::White, 1, 1000).unwrap();
+/// assert!(play == Point::new(2, 4) || play == Point::new(6, 4));
+/// ```
+pub fn threat_space_search(
+    board: &GomokuBoard,
+    attacker: Piece,
+    depth: usize,
+    node_limit: usize,
+) -> Option<Point> {
+    if board.is_over() || board.turn() != attacker {
+        return None;
+    }
+    ThreatSearch {
+        attacker,
+        nodes: 0,
+        node_limit,
+    }
+    .search(board, depth)
+}
+
+struct ThreatSearch {
+    attacker: Piece,
+    nodes: usize,
+    node_limit: usize,
+}
+impl ThreatSearch {
+    fn search(&mut self, board: &GomokuBoard, depth: usize) -> Option<Point> {
+        self.nodes += 1;
+        if self.nodes > self.node_limit {
+            return None;
+        }
+        let (attacker, defender) = (self.attacker, self.attacker.flip());
+        if let Some(&win) = board.winning_points(attacker).first() {
+            return Some(win);
+        }
+        if depth == 0 {
+            return None;
+        }
+        let must_block = board.winning_points(defender);
+        if must_block.len() > 1 {
+            return None;
+        }
+        for play in candidates(board, attacker) {
+            if !must_block.is_empty() && must_block[0] != play {
+                continue;
+            }
+            // a threat needs at least two more of the attacker's stones on one of its lines
+            let attacker_cell: Cell = attacker.into();
+            let near_line = FOUR_DIRECTIONS.iter().any(|&dir| {
+                (-4..=4)
+                    .filter_map(|k| board.step(play, dir, k))
+                    .filter(|&p| board.get(p) == Ok(attacker_cell))
+                    .count()
+                    >= 2
+            });
+            if !near_line {
+                continue;
+            }
+            let Ok(next) = board.clone().place(play, attacker) else {
+                continue;
+            };
+            // the defender had no five before (or `play` blocked it), and `play` can't give it one.
+            // the attacker had none either, so any new one goes through `play`
+            let mut fives: Vec<_> = FOUR_DIRECTIONS
+                .iter()
+                .flat_map(|&dir| fives_along(&next, play, dir, attacker, &[]))
+                .collect();
+            fives.sort_by_key(|p| (p.y, p.x));
+            fives.dedup();
+            let replies = if !fives.is_empty() {
+                fives
+            } else {
+                let next = &next;
+                let mut replies: Vec<_> = FOUR_DIRECTIONS
+                    .iter()
+                    .flat_map(|&dir| {
+                        open_four_points(next, play, dir, attacker)
+                            .into_iter()
+                            .flat_map(move |q| {
+                                let mut points = fives_along(next, play, dir, attacker, &[q]);
+                                points.push(q);
+                                points
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+                if replies.is_empty() {
+                    continue; // not a threat
+                }
+                // the defender can also answer with a four of its own
+                replies.extend(next.points().filter(|&at| makes_four(next, at, defender)));
+                replies.sort_by_key(|p| (p.y, p.x));
+                replies.dedup();
+                replies
+            };
+            let wins = replies.into_iter().all(|reply| {
+                match next.clone().place(reply, defender) {
+                    Ok(after) => self.search(&after, depth - 1).is_some(),
+                    Err(_) => true, // forbidden for black. not a defence
+                }
+            });
+            if wins {
+                return Some(play);
+            }
+        }
+        None
+    }
+}
+
+fn eval(board: &GomokuBoard, playing: Piece) -> isize {
+    static WEIGHTS: [isize; 6] = [0, 1, 10, 100, 1000, 100000];
+    let mut score = 0;
+    // every window of five cells in every direction
+    for at in board.points() {
+        for &dir in FOUR_DIRECTIONS.iter() {
+            if board.step(at, dir, 4).is_none() {
+                continue;
+            }
+            let (mut own, mut other) = (0, 0);
+            for i in 0..5 {
+                match board
+                    .get(board.step(at, dir, i).unwrap())
+                    .map(Option::<Piece>::from)
+                {
+                    Ok(Some(piece)) if piece == playing => own += 1,
+                    Ok(Some(_)) => other += 1,
+                    _ => {}
+                }
+            }
+            // a window is only worth something while only one player is in it
+            if other == 0 {
+                score += WEIGHTS[own];
+            } else if own == 0 {
+                score -= WEIGHTS[other];
+            }
+        }
+    }
+    score
+}
+
+/// returns best point to place for `ai_player`. None if it's not `ai_player`'s turn or the game is over.
+/// `strength` is taken as 1..=10, as othello's `Searcher::new` does.
+/// ```rust
+/// use boardgame_ai::ai::gomoku as ai;
+/// use boardgame_ai::rules::gomoku::*;
+/// use boardgame_ai::rules::othello::{Piece, Point};
+/// let board = GomokuBoard::new(15, GomokuRule::Renju);
+/// assert_eq!(ai::predict(&board, Piece::Black, 1), Some(Point::new(7, 7)));
+/// ```
+pub fn predict(board: &GomokuBoard, ai_player: Piece, strength: usize) -> Option<Point> {
+    if board.is_over() || board.turn() != ai_player {
+        return None;
+    }
+    if let Some(&win) = board.winning_points(ai_player).first() {
+        return Some(win);
+    }
+    let block = board
+        .winning_points(ai_player.flip())
+        .into_iter()
+        .find(|at| ai_player == Piece::White || !board.is_forbidden(at));
+    if block.is_some() {
+        return block;
+    }
+    let strength = strength.clamp(1, 10);
+    let (depth, node_limit) = (1 + strength / 2, 50 * (strength + 1));
+    let threats = threat_space_search(board, ai_player, depth, node_limit);
+    if threats.is_some() {
+        return threats;
+    }
+    let best = search::alpha_beta(board, 1 + strength / 5, 8, &eval).map(|(play, _)| play);
+    // the full-width search is too shallow to see threat sequences coming,
+    // so make sure the opponent doesn't get one after our move
+    let safe = |play: &Point| {
+        threat_space_search(&board.apply(*play), ai_player.flip(), depth, node_limit).is_none()
+    };
+    if best.as_ref().is_none_or(safe) {
+        return best;
+    }
+    // only the most promising moves are tried, as each one needs a threat search
+    let mut plays: Vec<_> = board
+        .moves()
+        .into_iter()
+        .map(|play| (eval(&board.apply(play), ai_player), play))
+        .collect();
+    plays.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+    // nothing helps if every move loses. then just play the best looking one
+    plays
+        .into_iter()
+        .take(10)
+        .map(|(_, play)| play)
+        .find(safe)
+        .or(best)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn double_three_is_found() {
+        let board = "
+            b.......b
+            .........
+            .....w...
+            .....w...
+            b..ww....
+            .........
+            .........
+            .........
+            b.......b
+        ";
+        let board = GomokuBoard::decode(board, 9, GomokuRule::FreeStyle).unwrap();
+        assert_eq!(board.turn(), Piece::White);
+        assert_eq!(threat_space_search(&board, Piece::White, 1, 10000), None);
+        assert_eq!(
+            threat_space_search(&board, Piece::White, 2, 10000),
+            Some(Point::new(5, 4))
+        );
+    }
+    #[test]
+    fn blocks_four() {
+        let board = "
+            .........
+            .........
+            .........
+            .........
+            .bbbbw...
+            .........
+            ...w.....
+            .....w...
+            .........
+        ";
+        let board = GomokuBoard::decode(board, 9, GomokuRule::Renju).unwrap();
+        assert_eq!(predict(&board, Piece::White, 3), Some(Point::new(0, 4)));
+    }
+}
@@ -0,0 +1,144 @@
+//! alpha-beta search for gravity othello.
+//! the evaluator is othello's, except that mobility is counted in columns, as that's what players choose from.
+use super::game::{Game, Outcome};
+use super::othello::{disc_sign, eval_positional_score, eval_win};
+use super::search;
+use crate::rules::gravity_othello;
+use crate::rules::othello::*;
+
+/// gravity othello as a `Game`. moves are columns, and a pass is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub board: Board,
+    pub playing: Piece,
+}
+impl Game for State {
+    type Move = Option<usize>;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.playing
+    }
+    fn moves(&self) -> Vec<Option<usize>> {
+        let possible = gravity_othello::placeable(&self.board, self.playing);
+        if !possible.is_empty() {
+            possible.into_iter().map(Some).collect()
+        } else if gravity_othello::placeable(&self.board, self.playing.flip()).is_empty() {
+            vec![] // game over
+        } else {
+            vec![None]
+        }
+    }
+    fn apply(&self, play: Option<usize>) -> Self {
+        let board = match play {
+            Some(column) => {
+                gravity_othello::place(self.board.clone(), column, self.playing).unwrap()
+            }
+            None => self.board.clone(),
+        };
+        State {
+            board,
+            playing: self.playing.flip(),
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        gravity_othello::is_over(&self.board)
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.board.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+fn eval(state: &Board, playing: Piece) -> isize {
+    // discs and positions are turned around on anti-othello, as in othello's evaluator.
+    // mobility isn't: having more choices is still an advantage there.
+    let base_score =
+        (state.score(playing) as isize - state.score(playing.flip()) as isize) * disc_sign(state);
+    let flexibility_score = eval_flexibility(state, playing);
+    let win_score = eval_win(state.clone(), playing);
+    let positional_score = eval_positional_score(state.clone(), playing);
+    base_score + flexibility_score + win_score + positional_score
+}
+fn eval_flexibility(state: &Board, playing: Piece) -> isize {
+    let player_oppotunity = gravity_othello::placeable(state, playing).len() as isize;
+    let opponent_oppotunity = gravity_othello::placeable(state, playing.flip()).len() as isize;
+    // there are only `width` choices at most, so each one is worth more than on othello
+    let base = (player_oppotunity - opponent_oppotunity) * 5;
+    let unplaceable = if player_oppotunity == 0 {
+        -1
+    } else if opponent_oppotunity == 0 {
+        1
+    } else {
+        0
+    };
+    base + unplaceable * 100
+}
+
+/// how many plies to search for a strength of 1..=10, as in othello's `generate_ai_play`.
+/// 1 + strength / 2, so 1..=6: each ply is at most `width` columns wide, but the search is full width.
+pub fn plies(strength: usize) -> usize {
+    1 + strength.min(10) / 2
+}
+
+/// returns best column to drop into for `ai_player`, searching `depth` plies ahead.
+/// will return None if there were no columns that AI can drop into.
+/// ```rust
+/// use boardgame_ai::ai::gravity_othello as ai;
+/// use boardgame_ai::rules::gravity_othello;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = gravity_othello::new(8, 8);
+/// let next_play = ai::predict(&board, Piece::Black, 4).unwrap();
+/// assert!(gravity_othello::placeable(&board, Piece::Black).contains(&next_play));
+/// ```
+pub fn predict(state: &Board, ai_player: Piece, depth: usize) -> Option<usize> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(&state.board, playing);
+    search::alpha_beta(&state, depth, usize::MAX, &eval).and_then(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn takes_bottom_corner() {
+        let board = "
+            ......
+            ......
+            ......
+            ......
+            ...bw.
+            .wwwb.
+        ";
+        let board = Board::decode(board, 6, 6).unwrap();
+        // column 0 lands on the corner, column 4 lands on top of the stack
+        assert_eq!(gravity_othello::placeable(&board, Piece::Black), vec![0, 4]);
+        let next_play = predict(&board, Piece::Black, 1).unwrap();
+        assert_eq!(next_play, 0);
+    }
+    #[test]
+    fn misere_avoids_bottom_corner() {
+        let board = "
+            ......
+            ......
+            ......
+            ......
+            ...bw.
+            .wwwb.
+        ";
+        let board = Board::decode(board, 6, 6).unwrap();
+        let misere = board.clone().with_win_condition(WinCondition::FewestDiscs);
+        // only the mobility term keeps its sign
+        assert_eq!(
+            eval(&misere, Piece::Black) + eval(&board, Piece::Black),
+            2 * eval_flexibility(&board, Piece::Black)
+        );
+        assert_eq!(predict(&misere, Piece::Black, 1), Some(4));
+    }
+}
@@ -0,0 +1,87 @@
+//! monte carlo tree search for hex.
+//! there's no good cheap evaluator for hex (what matters is how well groups connect, not how many stones there are),
+//! but random playouts are surprisingly good at judging connections, so `search::mcts` is used as is.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::hex::*;
+use crate::rules::othello::Piece;
+
+impl Game for HexGameBoard {
+    type Move = HexMove;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.turn()
+    }
+    fn moves(&self) -> Vec<HexMove> {
+        if self.is_over() {
+            return vec![];
+        }
+        let mut moves: Vec<_> = self.placeable().into_iter().map(HexMove::Place).collect();
+        if self.can_swap() {
+            moves.push(HexMove::Swap);
+        }
+        moves
+    }
+    fn apply(&self, play: HexMove) -> Self {
+        self.clone().play(play).unwrap()
+    }
+    fn is_terminal(&self) -> bool {
+        self.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.winner() {
+            None => Outcome::Draw, // not over yet. hex has no draws
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+/// returns best move for `ai_player` after `iterations` playouts. the same `seed` gives the same move.
+/// will return None if it's not `ai_player`'s turn or the game is over.
+/// ```rust
+/// use boardgame_ai::ai::hex as ai;
+/// use boardgame_ai::rules::hex::*;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = HexGameBoard::new(5, true);
+/// let play = ai::predict(&board, Piece::Black, 200, 42).unwrap();
+/// assert!(matches!(play, HexMove::Place(_)));
+/// assert_eq!(ai::predict(&board, Piece::White, 200, 42), None);
+/// ```
+pub fn predict(
+    board: &HexGameBoard,
+    ai_player: Piece,
+    iterations: usize,
+    seed: u64,
+) -> Option<HexMove> {
+    if board.turn() != ai_player {
+        return None;
+    }
+    search::mcts(board, iterations, seed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::othello::Point;
+    #[test]
+    fn swaps_a_strong_opening() {
+        // the centre of a small board wins for whoever has it
+        let board = HexGameBoard::new(3, true)
+            .place(Point::new(1, 1), Piece::Black)
+            .unwrap();
+        assert_eq!(predict(&board, Piece::White, 3000, 1), Some(HexMove::Swap));
+    }
+    #[test]
+    fn only_plays_on_its_turn() {
+        let input = "
+            . b w
+             w b .
+              . . .
+        ";
+        let board = HexGameBoard::decode(input, 3).unwrap();
+        assert_eq!(predict(&board, Piece::White, 100, 1), None);
+        assert!(predict(&board, Piece::Black, 100, 1).is_some());
+    }
+}
@@ -0,0 +1,127 @@
+//! alpha-beta search for hex othello.
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::hex_othello::*;
+use crate::rules::othello::Piece;
+
+/// hex othello as a `Game`. a pass is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub board: HexBoard,
+    pub playing: Piece,
+}
+impl Game for State {
+    type Move = Option<HexPoint>;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.playing
+    }
+    fn moves(&self) -> Vec<Option<HexPoint>> {
+        let possible = self.board.placeable(self.playing);
+        if !possible.is_empty() {
+            possible.into_iter().map(Some).collect()
+        } else if self.board.placeable(self.playing.flip()).is_empty() {
+            vec![] // game over
+        } else {
+            vec![None]
+        }
+    }
+    fn apply(&self, play: Option<HexPoint>) -> Self {
+        let board = match play {
+            Some(at) => self.board.clone().place(at, self.playing).unwrap(),
+            None => self.board.clone(),
+        };
+        State {
+            board,
+            playing: self.playing.flip(),
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        self.board.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.board.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+// game over is scored by the search, so this only needs to judge positions in the middle of the game.
+fn eval(state: &HexBoard, playing: Piece) -> isize {
+    let base_score = state.score(playing) as isize - state.score(playing.flip()) as isize;
+    let flexibility_score = (state.placeable(playing).len() as isize
+        - state.placeable(playing.flip()).len() as isize)
+        * 3;
+    base_score + flexibility_score + eval_positional_score(state, playing)
+}
+fn eval_positional_score(state: &HexBoard, playing: Piece) -> isize {
+    let radius = state.radius();
+    state
+        .points()
+        .map(|point| {
+            let on_rim = point.ring() == radius;
+            // the six corners of the hexagon are the only cells with two coordinates at the rim,
+            // and just like othello, they can never be flipped.
+            let at_rim = [point.q, point.r, point.s()]
+                .iter()
+                .filter(|c| c.unsigned_abs() == radius)
+                .count();
+            let score = if at_rim >= 2 {
+                20 // corner
+            } else if on_rim {
+                3 // side of the board, more stable than middle
+            } else if point.ring() == radius - 1 {
+                -3 // gives away the side
+            } else {
+                0
+            };
+            match state.get(point) {
+                Ok(cell) if cell == playing.into() => score,
+                Ok(cell) if cell == playing.flip().into() => -score,
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+/// returns best point to place for `ai_player`, searching `depth` plies ahead.
+/// will return None if there were no cells that AI can place.
+/// ```rust
+/// use boardgame_ai::ai::hex_othello as ai;
+/// use boardgame_ai::rules::hex_othello::*;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = HexBoard::new(3);
+/// let next_play = ai::predict(&board, Piece::Black, 3).unwrap();
+/// assert!(board.placeable(Piece::Black).contains(&next_play));
+/// ```
+pub fn predict(state: &HexBoard, ai_player: Piece, depth: usize) -> Option<HexPoint> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(&state.board, playing);
+    search::alpha_beta(&state, depth, usize::MAX, &eval).and_then(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn takes_corner() {
+        let board = "
+              . . w
+             . . b .
+            . . w b .
+             . w b .
+              . . .
+        ";
+        let board = HexBoard::decode(board, 2).unwrap();
+        // (2, 0) is a corner, (1, 1) flips as many but isn't
+        assert!(board.placeable(Piece::White).contains(&HexPoint::new(1, 1)));
+        let next_play = predict(&board, Piece::White, 1).unwrap();
+        assert_eq!(next_play, HexPoint::new(2, 0));
+    }
+}
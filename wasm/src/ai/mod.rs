@@ -1 +1,12 @@
+pub mod ataxx;
+pub mod connect_four;
+pub mod cube_othello;
+pub mod draughts;
+pub mod game;
+pub mod gomoku;
+pub mod gravity_othello;
+pub mod hex;
+pub mod hex_othello;
 pub mod othello;
+pub mod rolit;
+pub mod search;
@@ -1,8 +1,65 @@
+use super::game::{Game, Outcome};
+use super::search;
 use crate::rules::othello as rules;
 use rules::*;
+use wasm_bindgen::prelude::*;
 
+/// othello as a `Game`. a pass is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct State {
+    pub board: Board,
+    pub playing: Piece,
+}
+impl Game for State {
+    type Move = Option<Point>;
+    type Player = Piece;
+
+    fn side_to_move(&self) -> Piece {
+        self.playing
+    }
+    fn moves(&self) -> Vec<Option<Point>> {
+        let possible = self.board.placeable(self.playing);
+        if !possible.is_empty() {
+            possible.into_iter().map(Some).collect()
+        } else if self.board.placeable(self.playing.flip()).is_empty() {
+            vec![] // game over
+        } else {
+            vec![None]
+        }
+    }
+    fn apply(&self, play: Option<Point>) -> Self {
+        let board = match play {
+            Some(at) => self.board.clone().place(at, self.playing).unwrap(),
+            None => self.board.clone(),
+        };
+        State {
+            board,
+            playing: self.playing.flip(),
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        self.board.is_over()
+    }
+    fn result(&self, player: Piece) -> Outcome {
+        match self.board.winner() {
+            None => Outcome::Draw,
+            Some(winner) if winner == player => Outcome::Win,
+            Some(_) => Outcome::Loss,
+        }
+    }
+}
+
+// +1 if having more discs is good for the player, -1 if it's bad (anti-othello).
+pub(crate) fn disc_sign(state: &Board) -> isize {
+    match state.win_condition {
+        WinCondition::MostDiscs => 1,
+        WinCondition::FewestDiscs => -1,
+    }
+}
 fn eval(state: Board, playing: Piece) -> isize {
-    let base_score = state.score(playing) as isize;
+    // a differential, so that `eval(s, a) == -eval(s, b)` as the negamax searches expect.
+    let base_score =
+        (state.score(playing) as isize - state.score(playing.flip()) as isize) * disc_sign(&state);
     let flexibility_score = eval_flexibility(&state, playing);
     let win_score = eval_win(state.clone(), playing);
     let positional_score = eval_positional_score(state, playing);
@@ -22,36 +79,35 @@ fn eval_flexibility(state: &Board, playing: Piece) -> isize {
     };
     base + unplaceable * 100
 }
-fn eval_win(state: Board, playing: Piece) -> isize {
+pub(crate) fn eval_win(state: Board, playing: Piece) -> isize {
+    let sign = disc_sign(&state);
     let playing: Cell = playing.into();
     let opponent: Cell = playing.flip();
     let cells = state.cells();
     if cells.iter().all(|(_, val)| *val != opponent) {
-        isize::MAX / 2 // you won
+        sign * (isize::MAX / 2) // opponent is wiped out. you won, or lost on anti-othello
     } else if cells.iter().all(|(_, val)| *val != playing) {
-        isize::MIN / 2 // you lost
+        sign * (isize::MIN / 2) // you are wiped out
     } else {
         0
     }
 }
-fn eval_positional_score(state: Board, playing: Piece) -> isize {
-    let size = state.size();
-    state
+pub(crate) fn eval_positional_score(state: Board, playing: Piece) -> isize {
+    if state.topology == Topology::Torus {
+        return 0; // a torus has no corners nor edges, so every cell is alike
+    }
+    let (width, height) = (state.width(), state.height());
+    // stable discs are a liability on anti-othello, so the whole table is inverted there.
+    let sign = disc_sign(&state);
+    sign * state
         .cells()
         .into_iter()
         .map(|(point, cell)| {
             // mirrored and squashed to top left quarter for easier calc.
+            // each axis is mirrored on its own, so this works for rectangular boards too.
             let squashed = Point {
-                x: if point.x > size / 2 {
-                    size - point.x - 1
-                } else {
-                    point.x
-                },
-                y: if point.y > size / 2 {
-                    size - point.y - 1
-                } else {
-                    point.y
-                },
+                x: point.x.min(width - point.x - 1),
+                y: point.y.min(height - point.y - 1),
             };
             // asked chat gpt for the scores. I'm not familiar with othello nor AI.
             let score = if squashed.x == 0 && squashed.y == 0 {
@@ -72,14 +128,42 @@ fn eval_positional_score(state: Board, playing: Piece) -> isize {
             (score, cell)
         })
         .map(|(mul, cell)| match cell {
-            Cell::Empty => 0,
+            Cell::Empty | Cell::Blocked => 0,
             _ if cell == playing.into() => mul,
             _ => -mul,
         })
-        .sum()
+        .sum::<isize>()
+}
+/// what the search scores positions by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Evaluator {
+    /// discs, mobility, and corners and edges. what `predict` uses.
+    #[default]
+    Standard,
+    /// the disc count alone, which plays greedily. mostly a baseline to measure the others against.
+    Discs,
+    /// corners and edges alone.
+    Positional,
 }
+impl Evaluator {
+    fn eval(&self, state: Board, playing: Piece) -> isize {
+        match self {
+            Evaluator::Standard => eval(state, playing),
+            Evaluator::Discs => {
+                let discs = state.score(playing) as isize - state.score(playing.flip()) as isize;
+                discs * disc_sign(&state) + eval_win(state, playing)
+            }
+            Evaluator::Positional => {
+                eval_positional_score(state.clone(), playing) + eval_win(state, playing)
+            }
+        }
+    }
+}
+
 /// returns best point to place.
 /// the larger `rec` is, the better the AI plays. (and more resouce this program consumes)
+/// it's capped at 10, as the search would explode beyond that.
+/// it searches `rec / 2 + 1` plies ahead, looking at only `width_lim` moves on each ply.
 /// will return None if there were no cells that AI can place.
 /// ```rust
 /// use boardgame_ai::ai::othello as ai;
@@ -90,66 +174,208 @@ fn eval_positional_score(state: Board, playing: Piece) -> isize {
 /// .wwb
 /// .bbb
 /// ";
-/// let board = Board::decode(board, 4).unwrap();
+/// let board = Board::decode(board, 4, 4).unwrap();
 /// let next_play = ai::predict(&board, Piece::Black, 1, 10).unwrap();
 /// assert_eq!(next_play, Point::new(1, 1));
 /// ```
 pub fn predict(state: &Board, ai_player: Piece, rec: usize, width_lim: usize) -> Option<Point> {
-    predict_rec(state, ai_player, rec, width_lim)
-        .first()
-        .map(|val| val.0)
+    predict_with(state, ai_player, rec, width_lim, Evaluator::Standard)
+}
+/// `predict`, scoring positions with `evaluator` instead.
+pub fn predict_with(
+    state: &Board,
+    ai_player: Piece,
+    rec: usize,
+    width_lim: usize,
+    evaluator: Evaluator,
+) -> Option<Point> {
+    // any deeper and the search takes forever
+    let rec = rec.min(10);
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| evaluator.eval(state.board.clone(), playing);
+    search::alpha_beta(&state, plies(rec), width_lim, &eval).and_then(|(play, _)| play)
+}
+
+/// how many plies `predict` searches ahead for `rec`.
+pub fn plies(rec: usize) -> usize {
+    rec / 2 + 1
 }
 
-/// returns vec sorted by score.
-fn predict_rec(
+/// searches exactly `plies` plies ahead, looking at `width_lim` moves on each, and returns the best move with its score.
+/// the move is None for a pass, and there's no move at all when the game is over.
+/// running it with 1, 2, ... plies gives an answer early and improves on it, which `predict` doesn't.
+/// ```rust
+/// use boardgame_ai::ai::othello as ai;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::new(8, 8);
+/// let (play, _) = ai::predict_plies(&board, Piece::Black, ai::plies(4), 4).unwrap();
+/// assert_eq!(play, ai::predict(&board, Piece::Black, 4, 4));
+/// ```
+pub fn predict_plies(
+    state: &Board,
+    ai_player: Piece,
+    plies: usize,
+    width_lim: usize,
+) -> Option<(Option<Point>, isize)> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(state.board.clone(), playing);
+    search::alpha_beta(&state, plies, width_lim, &eval)
+}
+
+/// every move `ai_player` can make with its score, best first, searched like `predict`.
+/// the scores are in the evaluator's own units, not discs. a pass is `None`.
+/// ```rust
+/// use boardgame_ai::ai::othello as ai;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::new(8, 8);
+/// let ranked = ai::rank(&board, Piece::Black, 2, 10);
+/// assert_eq!(ranked.len(), 4);
+/// // the opening moves are all alike
+/// assert!(ranked.iter().all(|&(_, score)| score == ranked[0].1));
+/// ```
+pub fn rank(
     state: &Board,
     ai_player: Piece,
     rec: usize,
     width_lim: usize,
-) -> Vec<(Point, isize)> {
-    assert!(
-        rec <= 10,
-        "rec should not be larger than 10, otherwise the order will explode"
-    );
-    let possible = state.placeable(ai_player);
-    let mut possible: Vec<_> = possible
-        .into_iter()
-        .map(|play| {
-            let next = state.clone().place(play, ai_player).unwrap();
-            (play, eval(next, ai_player))
-        })
-        .collect();
-    possible.sort_by_key(|v| v.1);
-    possible.reverse();
-    possible.truncate(width_lim);
-    if rec == 0 {
-        return possible;
-    }
-    let mut play_score_map = possible
-        .into_iter()
-        .map(|(init_ai_play, init_score)| {
-            // FIXME: this probably contains some logic duplication, but I'm not smart enough to fix it
-            let after_ai_board = state.clone().place(init_ai_play, ai_player).unwrap();
-            let human_plays = predict_rec(&after_ai_board, ai_player.flip(), 0, 1);
-            let mut after_human = after_ai_board.clone();
-            if let Some((play, _)) = human_plays.first() {
-                after_human = after_human.place(*play, ai_player.flip()).unwrap();
-            }
-            let next_ai_score = predict_rec(&after_human, ai_player, rec - 1, 2)
-                .first()
-                .map(|(play, next_score)| {
-                    let board = after_human.clone().place(*play, ai_player).unwrap();
-                    let score = eval(board, ai_player);
-                    score + next_score
-                })
-                .unwrap_or_else(|| eval(after_human, ai_player));
-            let total_score = init_score + next_ai_score;
-            (init_ai_play, total_score)
-        })
-        .collect::<Vec<_>>();
-    play_score_map.sort_by_key(|play_score| play_score.1);
-    play_score_map.reverse();
-    play_score_map
+) -> Vec<(Option<Point>, isize)> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(state.board.clone(), playing);
+    search::rank_moves(&state, plies(rec), width_lim, &eval)
+}
+
+/// what a search did. a pass is `None`.
+pub type Report = search::Report<Option<Point>>;
+impl Report {
+    /// the principal variation in standard notation, like `f5 d6 pass c3`.
+    pub fn pv_notation(&self) -> String {
+        let moves: Vec<_> = self
+            .pv
+            .iter()
+            .map(|play| play.map_or("pass".to_string(), |at| at.notation()))
+            .collect();
+        moves.join(" ")
+    }
+    /// everything on one line, like `depth 4, 1520 nodes, 3 tt hits, 12 ms, 126 knps, pv f5 d6 c3 f4`.
+    pub fn summary(&self) -> String {
+        format!(
+            "depth {}, {} nodes, {} tt hits, {:.0} ms, {:.0} knps, pv {}",
+            self.depth,
+            self.nodes,
+            self.tt_hits,
+            self.elapsed_ms,
+            self.nps() / 1000.0,
+            self.pv_notation()
+        )
+    }
+}
+
+/// `predict`, and what the search did. it deepens one ply at a time with a transposition table,
+/// which finds the same move as `predict`, usually in fewer nodes.
+/// ```rust
+/// use boardgame_ai::ai::othello as ai;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::new(8, 8);
+/// let (play, report) = ai::predict_report(&board, Piece::Black, 4, 4);
+/// assert_eq!(play, ai::predict(&board, Piece::Black, 4, 4));
+/// assert_eq!(report.depth, ai::plies(4));
+/// assert!(report.pv_notation().starts_with(&play.unwrap().notation()));
+/// ```
+pub fn predict_report(
+    state: &Board,
+    ai_player: Piece,
+    rec: usize,
+    width_lim: usize,
+) -> (Option<Point>, Report) {
+    let mut search = incremental(state, ai_player, plies(rec.min(10)), width_lim);
+    while !search.step(usize::MAX) {}
+    let play = search.best().and_then(|(play, _)| play);
+    (play, search.report())
+}
+
+pub(crate) fn incremental(
+    state: &Board,
+    ai_player: Piece,
+    plies: usize,
+    width_lim: usize,
+) -> search::Incremental<State> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+    };
+    let eval = |state: &State, playing: Piece| eval(state.board.clone(), playing);
+    search::Incremental::new(state, plies, width_lim, eval)
+}
+
+/// a search that JS runs a slice at a time, e.g. between animation frames, instead of blocking in `predict`.
+/// it deepens one ply at a time up to what `predict` searches for `strength`, so it can be stopped early
+/// and still has a move, which is the best one found so far.
+#[wasm_bindgen]
+pub struct Searcher {
+    search: search::Incremental<State>,
+}
+/// where a `Searcher` is at. see `Report` for the numbers.
+#[wasm_bindgen]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchProgress {
+    /// true once it has searched as deep as it goes, or the game is over.
+    pub finished: bool,
+    /// plies searched for `best`. 0 until the first ply is done.
+    pub depth: usize,
+    pub nodes: usize,
+    pub tt_hits: usize,
+    pub elapsed_ms: f64,
+    pub nps: f64,
+    best: Option<Point>,
+    pv: String,
+}
+#[wasm_bindgen]
+impl SearchProgress {
+    /// the best move so far. None before the first ply is done, and when the AI has to pass.
+    pub fn best(&self) -> Option<Point> {
+        self.best
+    }
+    /// the principal variation, like `f5 d6 pass c3`.
+    pub fn pv(&self) -> String {
+        self.pv.clone()
+    }
+}
+#[wasm_bindgen]
+impl Searcher {
+    /// `strength` is as in `generate_ai_play`.
+    pub fn new(board: &Board, ai_player: Piece, strength: usize) -> Searcher {
+        let strength = strength.clamp(1, 10);
+        Searcher {
+            search: incremental(board, ai_player, plies(strength), strength),
+        }
+    }
+    /// searches about `node_budget` more positions. an 8x8 board takes a few microseconds per position.
+    pub fn step(&mut self, node_budget: usize) -> SearchProgress {
+        self.search.step(node_budget);
+        self.progress()
+    }
+    pub fn progress(&self) -> SearchProgress {
+        let report = self.search.report();
+        SearchProgress {
+            finished: self.search.is_finished(),
+            depth: report.depth,
+            nodes: report.nodes,
+            tt_hits: report.tt_hits,
+            elapsed_ms: report.elapsed_ms,
+            nps: report.nps(),
+            best: self.search.best().and_then(|(play, _)| play),
+            pv: report.pv_notation(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -163,11 +389,64 @@ mod test {
             ww.b
             ....
         ";
-        let board = Board::decode(board, 4).unwrap();
+        let board = Board::decode(board, 4, 4).unwrap();
         let next_play = predict(&board, Piece::Black, 0, 10).unwrap();
         assert_eq!(next_play, Point::new(0, 3));
     }
     #[test]
+    fn anti_othello_prediction() {
+        let board = "
+            bbbb
+            wwww
+            ww.b
+            ....
+        ";
+        let board = Board::decode(board, 4, 4)
+            .unwrap()
+            .with_win_condition(WinCondition::FewestDiscs);
+        // (0, 3) flips the most and takes a corner, which is the worst on anti-othello
+        let next_play = predict(&board, Piece::Black, 0, 10).unwrap();
+        assert_ne!(next_play, Point::new(0, 3));
+    }
+    #[test]
+    fn eval_is_zero_sum() {
+        let board = "
+            bbbb
+            wwww
+            ww.b
+            ....
+        ";
+        let board = Board::decode(board, 4, 4).unwrap();
+        for condition in [WinCondition::MostDiscs, WinCondition::FewestDiscs]
+            .iter()
+            .copied()
+        {
+            let board = board.clone().with_win_condition(condition);
+            assert_ne!(eval(board.clone(), Piece::Black), 0);
+            assert_eq!(
+                eval(board.clone(), Piece::Black),
+                -eval(board.clone(), Piece::White)
+            );
+        }
+    }
+    #[test]
+    fn evaluators() {
+        let board = "
+            .wb...
+            ......
+            ......
+            ..wwwb
+            ......
+            ......
+        ";
+        let board = Board::decode(board, 6, 6).unwrap();
+        // (1, 3) flips three, while (0, 0) flips one but takes the corner
+        let discs = predict_with(&board, Piece::Black, 0, 10, Evaluator::Discs);
+        assert_eq!(discs, Some(Point::new(1, 3)));
+        let positional = predict_with(&board, Piece::Black, 0, 10, Evaluator::Positional);
+        assert_eq!(positional, Some(Point::new(0, 0)));
+    }
+    #[test]
     fn when_ai_cannot_place() {
         let board = "
             ww.b
@@ -175,11 +454,34 @@ mod test {
             w..b
             bwbw
         ";
-        let board = Board::decode(board, 4).unwrap();
+        let board = Board::decode(board, 4, 4).unwrap();
         let next_play = predict(&board, Piece::Black, 0, 10);
         assert_eq!(next_play, None);
     }
     #[test]
+    fn searcher_matches_predict() {
+        let board = Board::new(8, 8);
+        let first = board.placeable(Piece::Black)[0];
+        let board = board.place(first, Piece::Black).unwrap();
+        let mut searcher = Searcher::new(&board, Piece::White, 6);
+        let first = searcher.step(1);
+        assert!(!first.finished);
+        assert_eq!((first.depth, first.best()), (0, None));
+        let mut depths = vec![];
+        let last = loop {
+            let progress = searcher.step(50);
+            depths.push(progress.depth);
+            if progress.finished {
+                break progress;
+            }
+        };
+        assert!(depths.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(last.depth, plies(6));
+        assert_eq!(last.best(), predict(&board, Piece::White, 6, 6));
+        // it's over, so more steps change nothing
+        assert_eq!(searcher.step(1000), last);
+    }
+    #[test]
     fn recursion_should_terminate() {
         let board = "
             ............
@@ -195,7 +497,7 @@ mod test {
             .bbb........
             ..bb........
         ";
-        let board = Board::decode(board, 12).unwrap();
+        let board = Board::decode(board, 12, 12).unwrap();
         let next_play = predict(&board, Piece::Black, 5, 3);
         // NOTE: it takes around 10x~ more time on test than on wasm, because test runs on debug mode.
         // add --release flag to `cargo test` and it will magically be 10x faster.
@@ -0,0 +1,145 @@
+//! paranoid search for rolit: the AI assumes every other player is teaming up against it.
+//! this is a lot weaker than max-n in theory, but it turns rolit into a two-sided game,
+//! so it goes through `ai::search`'s alpha-beta like the othello variants do,
+//! which matters because rolit has a lot of legal moves (any cell next to a disc).
+use super::game::{Game, Outcome};
+use super::search;
+use crate::rules::othello::Point;
+use crate::rules::rolit::*;
+
+/// which side of the paranoid search a player is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// the AI.
+    Me,
+    /// every other player, as one.
+    Others,
+}
+
+/// rolit as a `Game` between `me` and everybody else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub board: RolitBoard,
+    pub playing: Color,
+    pub me: Color,
+}
+impl Game for State {
+    type Move = Point;
+    type Player = Side;
+
+    fn side_to_move(&self) -> Side {
+        if self.playing == self.me {
+            Side::Me
+        } else {
+            Side::Others
+        }
+    }
+    fn moves(&self) -> Vec<Point> {
+        // nobody has to pass in rolit, so this is only empty once the board is full
+        self.board.placeable(self.playing)
+    }
+    fn apply(&self, play: Point) -> Self {
+        State {
+            board: self.board.clone().place(play, self.playing).unwrap(),
+            playing: self.playing.next(self.board.players()),
+            me: self.me,
+        }
+    }
+    fn is_terminal(&self) -> bool {
+        self.board.is_over()
+    }
+    fn result(&self, player: Side) -> Outcome {
+        let leaders = self.board.leaders();
+        let mine = if leaders == [self.me] {
+            Outcome::Win
+        } else if leaders.contains(&self.me) {
+            Outcome::Draw
+        } else {
+            Outcome::Loss
+        };
+        match (player, mine) {
+            (Side::Me, _) | (_, Outcome::Draw) => mine,
+            (Side::Others, Outcome::Win) => Outcome::Loss,
+            (Side::Others, Outcome::Loss) => Outcome::Win,
+        }
+    }
+}
+
+// finished games are scored by the search, from `Game::result`
+fn eval(state: &RolitBoard, me: Color) -> isize {
+    let mine = eval_positional_score(state, me);
+    let best_opponent = state
+        .colors()
+        .iter()
+        .filter(|&&color| color != me)
+        .map(|&color| eval_positional_score(state, color))
+        .max()
+        .unwrap_or(0);
+    mine - best_opponent
+}
+fn eval_positional_score(state: &RolitBoard, player: Color) -> isize {
+    let (width, height) = (state.width, state.height);
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+        .filter(|&point| state.get(point) == Ok(Some(player)))
+        .map(|point| {
+            // same table as othello, squashed to the top left quarter.
+            let x = point.x.min(width - point.x - 1);
+            let y = point.y.min(height - point.y - 1);
+            let positional = if x == 0 && y == 0 {
+                20
+            } else if x <= 1 && y <= 1 {
+                -5
+            } else if x == 0 || y == 0 {
+                3
+            } else {
+                0
+            };
+            positional + 1 // every disc counts, unlike othello's evaluator
+        })
+        .sum()
+}
+
+/// returns best point to place for `ai_player`, searching `depth` placements ahead
+/// (counting every player's placement). will return None if the board is full.
+/// ```rust
+/// use boardgame_ai::ai::rolit as ai;
+/// use boardgame_ai::rules::rolit::*;
+/// use boardgame_ai::rules::othello::Point;
+/// let board = RolitBoard::decode("
+/// .yr.
+/// .bg.
+/// ....
+/// ....
+/// ", 4, 4, 4).unwrap();
+/// // the corner captures yellow
+/// let next_play = ai::predict(&board, Color::Red, 1).unwrap();
+/// assert_eq!(next_play, Point::new(0, 0));
+/// ```
+pub fn predict(state: &RolitBoard, ai_player: Color, depth: usize) -> Option<Point> {
+    let state = State {
+        board: state.clone(),
+        playing: ai_player,
+        me: ai_player,
+    };
+    let eval = |state: &State, side: Side| match side {
+        Side::Me => eval(&state.board, state.me),
+        Side::Others => -eval(&state.board, state.me),
+    };
+    search::alpha_beta(&state, depth, usize::MAX, &eval).map(|(play, _)| play)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn full_game_terminates() {
+        let mut board = RolitBoard::new(6, 6, 3);
+        let mut playing = Color::Red;
+        while let Some(play) = predict(&board, playing, 2) {
+            board = board.place(play, playing).unwrap();
+            playing = playing.next(board.players());
+        }
+        assert!(board.is_over());
+    }
+}
@@ -0,0 +1,701 @@
+//! search algorithms that work on any `Game`, so every game in `rules` can share them.
+use super::game::{Game, Outcome};
+use crate::utils::{now_ms, Rng};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// score of a won position. evaluators should stay well below this.
+pub const WIN_SCORE: isize = isize::MAX / 4;
+
+fn terminal_score<G: Game>(state: &G) -> isize {
+    match state.result(state.side_to_move()) {
+        Outcome::Win => WIN_SCORE,
+        Outcome::Loss => -WIN_SCORE,
+        Outcome::Draw => 0,
+    }
+}
+
+/// negamax with alpha-beta pruning, for two-player zero-sum games.
+/// returns the best move for `side_to_move` and its score, or None if the game is over.
+///
+/// - `depth` is in moves (plies). a move that keeps the same player on turn still counts as one.
+/// - only the `width` most promising moves of each node are searched (pass `usize::MAX` to search all),
+///   judged by `eval` right after the move. this is a beam search, so it can miss moves that look bad at first.
+/// - `eval(state, player)` scores `state` for `player`, and must be antisymmetric:
+///   `eval(s, a) == -eval(s, b)` for the two players `a` and `b`.
+///
+/// ```rust
+/// use boardgame_ai::ai::othello::State;
+/// use boardgame_ai::ai::search;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::decode("
+/// .wwb
+/// w...
+/// b...
+/// ....
+/// ", 4, 4).unwrap();
+/// let state = State { board, playing: Piece::Black };
+/// let eval = |s: &State, p: Piece| s.board.score(p) as isize - s.board.score(p.flip()) as isize;
+/// let (play, _) = search::alpha_beta(&state, 1, usize::MAX, &eval).unwrap();
+/// assert_eq!(play, Some(Point::new(0, 0)));
+/// ```
+pub fn alpha_beta<G: Game>(
+    state: &G,
+    depth: usize,
+    width: usize,
+    eval: &impl Fn(&G, G::Player) -> isize,
+) -> Option<(G::Move, isize)> {
+    if state.is_terminal() {
+        return None;
+    }
+    let me = state.side_to_move();
+    let mut best: Option<(G::Move, isize)> = None;
+    let mut alpha = -isize::MAX;
+    for (play, child) in children(state, depth.max(1), width, eval) {
+        let score = child_score(
+            me,
+            &child,
+            depth.saturating_sub(1),
+            width,
+            alpha,
+            isize::MAX,
+            eval,
+        );
+        if best.is_none() || score > alpha {
+            alpha = score;
+            best = Some((play, score));
+        }
+    }
+    best
+}
+
+/// like `alpha_beta`, but scores every move exactly instead of only proving the best one, so it's slower.
+/// returns the moves best first with their scores, or nothing if the game is over.
+/// every legal move is scored; `width` only narrows the search below them.
+/// ```rust
+/// use boardgame_ai::ai::othello::State;
+/// use boardgame_ai::ai::search;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::decode("
+/// .wwb
+/// w...
+/// b...
+/// ....
+/// ", 4, 4).unwrap();
+/// let state = State { board, playing: Piece::Black };
+/// let eval = |s: &State, p: Piece| s.board.score(p) as isize - s.board.score(p.flip()) as isize;
+/// let ranked = search::rank_moves(&state, 1, usize::MAX, &eval);
+/// // the only move takes every white disc, which wins
+/// assert_eq!(ranked, vec![(Some(Point::new(0, 0)), search::WIN_SCORE)]);
+/// ```
+pub fn rank_moves<G: Game>(
+    state: &G,
+    depth: usize,
+    width: usize,
+    eval: &impl Fn(&G, G::Player) -> isize,
+) -> Vec<(G::Move, isize)> {
+    if state.is_terminal() {
+        return vec![];
+    }
+    let me = state.side_to_move();
+    let mut ranked: Vec<_> = children(state, depth.max(1), usize::MAX, eval)
+        .into_iter()
+        .map(|(play, child)| {
+            let score = child_score(
+                me,
+                &child,
+                depth.saturating_sub(1),
+                width,
+                -isize::MAX,
+                isize::MAX,
+                eval,
+            );
+            (play, score)
+        })
+        .collect();
+    ranked.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    ranked
+}
+
+fn negamax<G: Game>(
+    state: &G,
+    depth: usize,
+    width: usize,
+    mut alpha: isize,
+    beta: isize,
+    eval: &impl Fn(&G, G::Player) -> isize,
+) -> isize {
+    if state.is_terminal() {
+        return terminal_score(state);
+    }
+    let me = state.side_to_move();
+    if depth == 0 {
+        return eval(state, me);
+    }
+    let mut best = -isize::MAX;
+    for (_, child) in children(state, depth, width, eval) {
+        let score = child_score(me, &child, depth - 1, width, alpha, beta, eval);
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
+
+// score of `child` for `me`, who made the move into it.
+fn child_score<G: Game>(
+    me: G::Player,
+    child: &G,
+    depth: usize,
+    width: usize,
+    alpha: isize,
+    beta: isize,
+    eval: &impl Fn(&G, G::Player) -> isize,
+) -> isize {
+    if child.side_to_move() == me {
+        negamax(child, depth, width, alpha, beta, eval)
+    } else {
+        -negamax(child, depth, width, -beta, -alpha, eval)
+    }
+}
+
+// legal moves and the states after them, best first.
+// ordering makes pruning a lot more effective, but it isn't worth an extra eval right above the leaves.
+fn children<G: Game>(
+    state: &G,
+    depth: usize,
+    width: usize,
+    eval: &impl Fn(&G, G::Player) -> isize,
+) -> Vec<(G::Move, G)> {
+    let me = state.side_to_move();
+    let children: Vec<_> = state
+        .moves()
+        .into_iter()
+        .map(|play| (play, state.apply(play)))
+        .collect();
+    if depth <= 1 && children.len() <= width {
+        return children;
+    }
+    let mut scored: Vec<_> = children
+        .into_iter()
+        .map(|(play, child)| {
+            let score = if child.is_terminal() {
+                match child.result(me) {
+                    Outcome::Win => WIN_SCORE,
+                    Outcome::Loss => -WIN_SCORE,
+                    Outcome::Draw => 0,
+                }
+            } else {
+                eval(&child, me)
+            };
+            (score, play, child)
+        })
+        .collect();
+    // stable, so equally scored moves keep the order of `moves`
+    scored.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+    scored.truncate(width);
+    scored
+        .into_iter()
+        .map(|(_, play, child)| (play, child))
+        .collect()
+}
+
+type Eval<G> = dyn Fn(&G, <G as Game>::Player) -> isize;
+
+/// what a search did, to show to people.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report<M> {
+    pub nodes: usize,
+    /// plies searched for `pv`.
+    pub depth: usize,
+    /// positions that were already in the transposition table, and weren't searched again.
+    pub tt_hits: usize,
+    /// spent searching, not counting pauses between steps.
+    pub elapsed_ms: f64,
+    /// the principal variation: the best move, the best answer to it, and so on.
+    pub pv: Vec<M>,
+}
+impl<M> Report<M> {
+    /// nodes per second.
+    pub fn nps(&self) -> f64 {
+        if self.elapsed_ms > 0.0 {
+            self.nodes as f64 * 1000.0 / self.elapsed_ms
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    /// the score is at least this.
+    Lower,
+    /// the score is at most this.
+    Upper,
+}
+// what's known about a position from searching it
+struct Entry<M> {
+    depth: usize,
+    score: isize,
+    bound: Bound,
+    best_move: Option<M>,
+    // only kept for exact scores
+    pv: Vec<M>,
+}
+
+// a node of `Incremental` being searched, like a call of `negamax`.
+struct Frame<G: Game> {
+    state: G,
+    me: G::Player,
+    depth: usize,
+    alpha: isize,
+    // alpha when the node was entered, to tell what kind of bound the score is
+    original_alpha: isize,
+    beta: isize,
+    best: isize,
+    best_move: Option<G::Move>,
+    pv: Vec<G::Move>,
+    children: Vec<(G::Move, G)>,
+    next: usize,
+}
+impl<G: Game> Frame<G> {
+    // `first` is searched first, if it's among the children
+    fn new(
+        state: &G,
+        depth: usize,
+        width: usize,
+        (alpha, beta): (isize, isize),
+        first: Option<G::Move>,
+        eval: &impl Fn(&G, G::Player) -> isize,
+    ) -> Self {
+        let mut children = children(state, depth, width, eval);
+        if let Some(index) = children.iter().position(|(play, _)| Some(*play) == first) {
+            children[..=index].rotate_right(1);
+        }
+        Frame {
+            state: state.clone(),
+            me: state.side_to_move(),
+            depth,
+            alpha,
+            original_alpha: alpha,
+            beta,
+            best: -isize::MAX,
+            best_move: None,
+            pv: Vec::new(),
+            children,
+            next: 0,
+        }
+    }
+    fn is_finished(&self) -> bool {
+        self.next >= self.children.len() || self.alpha >= self.beta
+    }
+    // takes the score of the last child taken for `me`, and the line after it.
+    fn take(&mut self, play: G::Move, score: isize, line: &[G::Move]) {
+        if self.best_move.is_none() || score > self.best {
+            self.best_move = Some(play);
+            self.pv.clear();
+            self.pv.push(play);
+            self.pv.extend_from_slice(line);
+        }
+        self.best = self.best.max(score);
+        self.alpha = self.alpha.max(score);
+    }
+    fn entry(&self) -> Entry<G::Move> {
+        let bound = if self.best <= self.original_alpha {
+            Bound::Upper
+        } else if self.best >= self.beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        let pv = if bound == Bound::Exact {
+            self.pv.clone()
+        } else {
+            Vec::new()
+        };
+        Entry {
+            depth: self.depth,
+            score: self.best,
+            bound,
+            best_move: self.best_move,
+            pv,
+        }
+    }
+}
+
+/// `alpha_beta` with iterative deepening and a transposition table, which can be paused after any number of nodes.
+/// it searches 1, 2, ... up to `max_depth` plies, so there is an answer as soon as the first depth is done,
+/// and each depth finds the same move as `alpha_beta` would. the table only helps with move ordering and
+/// positions reached twice, so the answers stay the same.
+///
+/// ```rust
+/// use boardgame_ai::ai::othello::State;
+/// use boardgame_ai::ai::search::{self, Incremental};
+/// use boardgame_ai::rules::othello::*;
+/// let state = State { board: Board::new(6, 6), playing: Piece::Black };
+/// let eval = |s: &State, p: Piece| s.board.score(p) as isize - s.board.score(p.flip()) as isize;
+/// let mut search = Incremental::new(state.clone(), 3, usize::MAX, eval);
+/// while !search.step(10) {}
+/// assert_eq!(search.depth(), 3);
+/// assert_eq!(search.best(), search::alpha_beta(&state, 3, usize::MAX, &eval));
+/// let report = search.report();
+/// assert_eq!(report.pv.len(), 3);
+/// assert_eq!(report.pv[0], search.best().unwrap().0);
+/// ```
+pub struct Incremental<G: Game + Eq + Hash> {
+    root: G,
+    max_depth: usize,
+    width: usize,
+    eval: Box<Eval<G>>,
+    table: HashMap<G, Entry<G::Move>>,
+    // the depth being searched, and the nodes on the way down to where it's at
+    searching: usize,
+    stack: Vec<Frame<G>>,
+    best: Option<(G::Move, isize)>,
+    pv: Vec<G::Move>,
+    nodes: usize,
+    tt_hits: usize,
+    elapsed_ms: f64,
+    finished: bool,
+}
+impl<G: Game + Eq + Hash> Incremental<G> {
+    pub fn new(
+        root: G,
+        max_depth: usize,
+        width: usize,
+        eval: impl Fn(&G, G::Player) -> isize + 'static,
+    ) -> Self {
+        let finished = root.is_terminal();
+        Incremental {
+            root,
+            max_depth: max_depth.max(1),
+            width,
+            eval: Box::new(eval),
+            table: HashMap::new(),
+            searching: 0,
+            stack: Vec::new(),
+            best: None,
+            pv: Vec::new(),
+            nodes: 0,
+            tt_hits: 0,
+            elapsed_ms: 0.0,
+            finished,
+        }
+    }
+
+    /// searches up to `nodes` more nodes, stopping early when a depth is done. returns true once the search is over.
+    pub fn step(&mut self, nodes: usize) -> bool {
+        if self.finished {
+            return true;
+        }
+        let started = now_ms();
+        let Incremental {
+            root,
+            max_depth,
+            width,
+            eval,
+            table,
+            searching,
+            stack,
+            best,
+            pv,
+            nodes: visited,
+            tt_hits,
+            finished,
+            ..
+        } = self;
+        let eval = |state: &G, player: G::Player| eval(state, player);
+        let first_move = |table: &HashMap<G, Entry<G::Move>>, state: &G| {
+            table.get(state).and_then(|entry| entry.best_move)
+        };
+        let mut budget = nodes.max(1);
+        while budget > 0 && !*finished {
+            budget -= 1;
+            *visited += 1;
+            let Some(top) = stack.last_mut() else {
+                // the root keeps the order of `alpha_beta`, so ties go the same way
+                *searching += 1;
+                let window = (-isize::MAX, isize::MAX);
+                stack.push(Frame::new(root, *searching, *width, window, None, &eval));
+                continue;
+            };
+            if top.is_finished() {
+                let done = stack.pop().expect("just looked at it");
+                let Some(parent) = stack.last_mut() else {
+                    *best = done.best_move.map(|play| (play, done.best));
+                    *pv = done.pv;
+                    *finished = best.is_none() || *searching >= *max_depth;
+                    break;
+                };
+                let (play, child) = &parent.children[parent.next - 1];
+                let score = if child.side_to_move() == parent.me {
+                    done.best
+                } else {
+                    -done.best
+                };
+                parent.take(*play, score, &done.pv);
+                table.insert(done.state.clone(), done.entry());
+                continue;
+            }
+            let (play, child) = &top.children[top.next];
+            top.next += 1;
+            let (play, same_side) = (*play, child.side_to_move() == top.me);
+            let sign = if same_side { 1 } else { -1 };
+            let (alpha, beta) = if same_side {
+                (top.alpha, top.beta)
+            } else {
+                (-top.beta, -top.alpha)
+            };
+            // leaves are scored right away, like the start of `negamax`
+            if child.is_terminal() {
+                top.take(play, sign * terminal_score(child), &[]);
+                continue;
+            }
+            if top.depth <= 1 {
+                top.take(play, sign * eval(child, child.side_to_move()), &[]);
+                continue;
+            }
+            let depth = top.depth - 1;
+            let known = table.get(child).filter(|entry| {
+                entry.depth == depth
+                    && match entry.bound {
+                        Bound::Exact => true,
+                        Bound::Lower => entry.score >= beta,
+                        Bound::Upper => entry.score <= alpha,
+                    }
+            });
+            if let Some(entry) = known {
+                *tt_hits += 1;
+                top.take(play, sign * entry.score, &entry.pv);
+                continue;
+            }
+            let first = first_move(table, child);
+            let frame = Frame::new(child, depth, *width, (alpha, beta), first, &eval);
+            stack.push(frame);
+        }
+        self.elapsed_ms += now_ms() - started;
+        self.finished
+    }
+
+    /// the best move and its score, from the deepest search that's done. None until one is, or if the game is over.
+    pub fn best(&self) -> Option<(G::Move, isize)> {
+        self.best
+    }
+    /// how deep `best` was searched.
+    pub fn depth(&self) -> usize {
+        if self.stack.is_empty() {
+            self.searching
+        } else {
+            self.searching - 1
+        }
+    }
+    /// nodes searched so far, over every depth.
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    pub fn report(&self) -> Report<G::Move> {
+        Report {
+            nodes: self.nodes,
+            depth: self.depth(),
+            tt_hits: self.tt_hits,
+            elapsed_ms: self.elapsed_ms,
+            pv: self.pv.clone(),
+        }
+    }
+}
+
+struct Node<G: Game> {
+    state: G,
+    play: Option<G::Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<G::Move>,
+    visits: f64,
+    // total reward for the player who made the move into this node
+    reward: f64,
+}
+
+/// monte carlo tree search (UCT) with uniformly random playouts.
+/// doesn't need an evaluator, and works for any number of players.
+/// returns the most visited move after `iterations` playouts, or None if the game is over.
+/// the same `seed` always gives the same answer.
+///
+/// ```rust
+/// use boardgame_ai::ai::othello::State;
+/// use boardgame_ai::ai::search;
+/// use boardgame_ai::rules::othello::*;
+/// let board = Board::decode("
+/// .wwb
+/// bbbb
+/// bbbb
+/// bbbb
+/// ", 4, 4).unwrap();
+/// let state = State { board, playing: Piece::Black };
+/// // the only move, and it wins
+/// let play = search::mcts(&state, 100, 42).unwrap();
+/// assert_eq!(play, Some(Point::new(0, 0)));
+/// ```
+pub fn mcts<G: Game>(state: &G, iterations: usize, seed: u64) -> Option<G::Move> {
+    if state.is_terminal() {
+        return None;
+    }
+    let mut rng = Rng::new(seed);
+    let mut tree = vec![Node {
+        state: state.clone(),
+        play: None,
+        parent: None,
+        children: Vec::new(),
+        untried: state.moves(),
+        visits: 0.0,
+        reward: 0.0,
+    }];
+    for _ in 0..iterations.max(1) {
+        // selection
+        let mut current = 0;
+        while tree[current].untried.is_empty() && !tree[current].children.is_empty() {
+            let parent_visits = tree[current].visits;
+            current = *tree[current]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    uct(&tree[a], parent_visits).total_cmp(&uct(&tree[b], parent_visits))
+                })
+                .unwrap(); // checked non-empty above
+        }
+        // expansion
+        if !tree[current].untried.is_empty() {
+            let index = rng.below(tree[current].untried.len());
+            let play = tree[current].untried.swap_remove(index);
+            let child_state = tree[current].state.apply(play);
+            let untried = if child_state.is_terminal() {
+                Vec::new()
+            } else {
+                child_state.moves()
+            };
+            tree.push(Node {
+                state: child_state,
+                play: Some(play),
+                parent: Some(current),
+                children: Vec::new(),
+                untried,
+                visits: 0.0,
+                reward: 0.0,
+            });
+            let child = tree.len() - 1;
+            tree[current].children.push(child);
+            current = child;
+        }
+        // simulation
+        let mut playout = tree[current].state.clone();
+        while !playout.is_terminal() {
+            let moves = playout.moves();
+            playout = playout.apply(moves[rng.below(moves.len())]);
+        }
+        // backpropagation
+        let mut node = Some(current);
+        while let Some(index) = node {
+            tree[index].visits += 1.0;
+            if let Some(parent) = tree[index].parent {
+                let mover = tree[parent].state.side_to_move();
+                tree[index].reward += match playout.result(mover) {
+                    Outcome::Win => 1.0,
+                    Outcome::Draw => 0.5,
+                    Outcome::Loss => 0.0,
+                };
+            }
+            node = tree[index].parent;
+        }
+    }
+    tree[0]
+        .children
+        .iter()
+        .max_by(|&&a, &&b| tree[a].visits.total_cmp(&tree[b].visits))
+        .and_then(|&child| tree[child].play)
+}
+
+fn uct<G: Game>(node: &Node<G>, parent_visits: f64) -> f64 {
+    if node.visits == 0.0 {
+        return f64::INFINITY;
+    }
+    node.reward / node.visits + std::f64::consts::SQRT_2 * (parent_visits.ln() / node.visits).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // nim with a single pile: take 1 to 3 stones, whoever takes the last stone wins.
+    // the winning move is always to leave a multiple of 4.
+    #[derive(Debug, Clone)]
+    struct Nim {
+        stones: usize,
+        playing: u8,
+    }
+    impl Game for Nim {
+        type Move = usize;
+        type Player = u8;
+        fn side_to_move(&self) -> u8 {
+            self.playing
+        }
+        fn moves(&self) -> Vec<usize> {
+            (1..=self.stones.min(3)).collect()
+        }
+        fn apply(&self, play: usize) -> Self {
+            Nim {
+                stones: self.stones - play,
+                playing: 1 - self.playing,
+            }
+        }
+        fn is_terminal(&self) -> bool {
+            self.stones == 0
+        }
+        fn result(&self, player: u8) -> Outcome {
+            // the player to move has nothing left to take, so the other one took the last stone
+            if player == self.playing {
+                Outcome::Loss
+            } else {
+                Outcome::Win
+            }
+        }
+    }
+
+    #[test]
+    fn alpha_beta_solves_nim() {
+        for stones in [5, 6, 7, 9, 10, 11] {
+            let state = Nim { stones, playing: 0 };
+            let (play, score) = alpha_beta(&state, stones, usize::MAX, &|_, _| 0).unwrap();
+            assert_eq!((stones - play) % 4, 0, "{stones} stones");
+            assert_eq!(score, WIN_SCORE);
+        }
+        let lost = Nim {
+            stones: 8,
+            playing: 0,
+        };
+        let (_, score) = alpha_beta(&lost, 8, usize::MAX, &|_, _| 0).unwrap();
+        assert_eq!(score, -WIN_SCORE);
+        assert!(alpha_beta(
+            &Nim {
+                stones: 0,
+                playing: 0
+            },
+            1,
+            usize::MAX,
+            &|_, _| 0
+        )
+        .is_none());
+    }
+    #[test]
+    fn mcts_solves_nim() {
+        for stones in [5, 6, 7] {
+            let state = Nim { stones, playing: 0 };
+            let play = mcts(&state, 2000, 1).unwrap();
+            assert_eq!((stones - play) % 4, 0, "{stones} stones");
+        }
+    }
+}
@@ -0,0 +1,486 @@
+//! plays othello matches between two engine configurations, and reports the Elo difference.
+//!
+//! ```sh
+//! cargo run --release --bin engine-match -- ab:6 ab:6:discs --games 200 --sprt 0 20
+//! ```
+//!
+//! engines are written as
+//!
+//! - `ab:<strength>[:<evaluator>]`: `ai::othello::predict_with`, where strength is 1..=10 like in the web version
+//!   and the evaluator is `standard` (default), `discs` or `positional`.
+//! - `mcts:<iterations>`: `search::mcts` with uniformly random playouts.
+//!
+//! every game starts from a balanced opening: one of the positions `--plies` moves in (4 by default) that
+//! the evaluator thinks is closest to even. each opening is played twice, with the engines swapping colours.
+//! games run on `--threads` threads (all cores by default).
+//!
+//! the Elo difference is the first engine's, with a 95% confidence interval. with `--sprt <elo0> <elo1>`,
+//! the match stops as soon as a sequential probability ratio test tells `elo0` from `elo1`,
+//! with 5% false positives and negatives.
+use boardgame_ai::ai::othello::{self as ai, Evaluator, State};
+use boardgame_ai::ai::search;
+use boardgame_ai::rules::othello::{Board, Piece, Point};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+const USAGE: &str = "usage: engine-match <engine> <engine> [--games <n>] [--threads <n>] [--size <n>] [--plies <n>] [--sprt <elo0> <elo1>]
+engines: ab:<strength>[:standard|discs|positional], mcts:<iterations>";
+/// error rates of the SPRT.
+const ALPHA: f64 = 0.05;
+const BETA: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Engine {
+    AlphaBeta {
+        strength: usize,
+        evaluator: Evaluator,
+    },
+    Mcts {
+        iterations: usize,
+    },
+}
+impl Engine {
+    fn parse(spec: &str) -> Result<Engine, String> {
+        let parts: Vec<_> = spec.split(':').collect();
+        let number = |part: &str| part.parse::<usize>().ok().filter(|&n| n > 0);
+        let engine = match parts.as_slice() {
+            ["ab", strength] | ["ab", strength, "standard"] => {
+                number(strength).map(|strength| Engine::AlphaBeta {
+                    strength,
+                    evaluator: Evaluator::Standard,
+                })
+            }
+            ["ab", strength, "discs"] => number(strength).map(|strength| Engine::AlphaBeta {
+                strength,
+                evaluator: Evaluator::Discs,
+            }),
+            ["ab", strength, "positional"] => number(strength).map(|strength| Engine::AlphaBeta {
+                strength,
+                evaluator: Evaluator::Positional,
+            }),
+            ["mcts", iterations] => {
+                number(iterations).map(|iterations| Engine::Mcts { iterations })
+            }
+            _ => None,
+        };
+        match engine {
+            Some(Engine::AlphaBeta { strength, .. }) if strength > 10 => {
+                Err(format!("strength is 1..=10: {}", spec))
+            }
+            Some(engine) => Ok(engine),
+            None => Err(format!("unknown engine: {}", spec)),
+        }
+    }
+
+    /// None is a pass.
+    fn play(&self, board: &Board, next: Piece, seed: u64) -> Option<Point> {
+        match *self {
+            Engine::AlphaBeta {
+                strength,
+                evaluator,
+            } => ai::predict_with(board, next, strength, strength, evaluator),
+            Engine::Mcts { iterations } => {
+                let state = State {
+                    board: board.clone(),
+                    playing: next,
+                };
+                search::mcts(&state, iterations, seed).flatten()
+            }
+        }
+    }
+}
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Engine::AlphaBeta {
+                strength,
+                evaluator,
+            } => {
+                let evaluator = match evaluator {
+                    Evaluator::Standard => "standard",
+                    Evaluator::Discs => "discs",
+                    Evaluator::Positional => "positional",
+                };
+                write!(f, "ab:{}:{}", strength, evaluator)
+            }
+            Engine::Mcts { iterations } => write!(f, "mcts:{}", iterations),
+        }
+    }
+}
+
+/// positions `plies` moves into the game, the most balanced first.
+fn openings(size: usize, plies: usize) -> Vec<(Board, Piece)> {
+    fn walk(board: Board, next: Piece, plies: usize, out: &mut Vec<(Board, Piece)>) {
+        if plies == 0 {
+            out.push((board, next));
+            return;
+        }
+        for at in board.placeable(next) {
+            let after = board
+                .clone()
+                .place(at, next)
+                .expect("this shouldn't happen");
+            walk(after, next.flip(), plies - 1, out);
+        }
+    }
+    let mut positions = Vec::new();
+    walk(Board::new(size, size), Piece::Black, plies, &mut positions);
+    let mut unique: Vec<(Board, Piece)> = Vec::new();
+    for position in positions {
+        if !unique.contains(&position) {
+            unique.push(position);
+        }
+    }
+    let mut scored: Vec<_> = unique
+        .into_iter()
+        .filter_map(|(board, next)| {
+            let best = ai::rank(&board, next, 0, usize::MAX).first()?.1;
+            Some((best.abs(), (board, next)))
+        })
+        .collect();
+    // stable, so equally balanced openings keep the order they were found in
+    scored.sort_by_key(|(imbalance, _)| *imbalance);
+    scored.into_iter().map(|(_, opening)| opening).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// plays a game from `opening`, and returns how it went for `first`, and the final score.
+fn play_game(
+    first: Engine,
+    second: Engine,
+    opening: &(Board, Piece),
+    first_is_black: bool,
+    seed: u64,
+) -> (Outcome, usize, usize) {
+    let (mut board, mut next) = opening.clone();
+    let first_piece = if first_is_black {
+        Piece::Black
+    } else {
+        Piece::White
+    };
+    let mut ply = 0;
+    while !board.is_over() {
+        let engine = if next == first_piece { first } else { second };
+        if let Some(at) = engine.play(&board, next, seed.wrapping_add(ply)) {
+            board = board
+                .place(at, next)
+                .expect("engines only play legal moves");
+        }
+        next = next.flip();
+        ply += 1;
+    }
+    let (own, other) = (board.score(first_piece), board.score(first_piece.flip()));
+    let outcome = match own.cmp(&other) {
+        std::cmp::Ordering::Greater => Outcome::Win,
+        std::cmp::Ordering::Equal => Outcome::Draw,
+        std::cmp::Ordering::Less => Outcome::Loss,
+    };
+    (outcome, own, other)
+}
+
+/// expected score for the stronger side of an `elo` difference.
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+fn elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+impl Tally {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+    fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+    fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+    // variance of a single game's score
+    fn variance(&self) -> f64 {
+        let s = self.score();
+        let n = self.games() as f64;
+        (self.wins as f64 * (1.0 - s).powi(2)
+            + self.draws as f64 * (0.5 - s).powi(2)
+            + self.losses as f64 * s.powi(2))
+            / n
+    }
+    /// the Elo difference and the margin of its 95% confidence interval.
+    /// None while one side has scored everything, as the difference is infinite then.
+    fn elo(&self) -> Option<(f64, f64)> {
+        let s = self.score();
+        if self.games() == 0 || s <= 0.0 || s >= 1.0 {
+            return None;
+        }
+        let error = 1.96 * (self.variance() / self.games() as f64).sqrt();
+        // the slope of `elo` at `s`, as the interval would run off to infinity near 0% and 100%
+        let slope = 400.0 / (10f64.ln() * s * (1.0 - s));
+        Some((elo(s), slope * error))
+    }
+    /// log-likelihood ratio of `elo1` against `elo0`, with the normal approximation of the trinomial model.
+    fn llr(&self, elo0: f64, elo1: f64) -> f64 {
+        let variance = self.variance();
+        if self.games() == 0 || variance == 0.0 {
+            return 0.0;
+        }
+        let (s0, s1) = (expected_score(elo0), expected_score(elo1));
+        self.games() as f64 * (s1 - s0) * (2.0 * self.score() - s0 - s1) / (2.0 * variance)
+    }
+}
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "+{} ={} -{}, score {:.1}%",
+            self.wins,
+            self.draws,
+            self.losses,
+            self.score() * 100.0
+        )?;
+        match self.elo() {
+            Some((elo, margin)) => write!(f, ", elo {:+.1} ± {:.1}", elo, margin),
+            None => write!(f, ", elo unbounded"),
+        }
+    }
+}
+
+struct Config {
+    engines: (Engine, Engine),
+    games: usize,
+    threads: usize,
+    size: usize,
+    plies: usize,
+    sprt: Option<(f64, f64)>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+    let mut engines = Vec::new();
+    let mut config = Config {
+        engines: (
+            Engine::Mcts { iterations: 1 },
+            Engine::Mcts { iterations: 1 },
+        ),
+        games: 100,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        size: 8,
+        plies: 4,
+        sprt: None,
+    };
+    fn value<T: std::str::FromStr>(
+        args: &mut impl Iterator<Item = String>,
+        name: &str,
+    ) -> Result<T, String> {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", name))?;
+        value
+            .parse()
+            .map_err(|_| format!("bad value for {}: {}", name, value))
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--games" => config.games = value(&mut args, &arg)?,
+            "--threads" => config.threads = value::<usize>(&mut args, &arg)?.max(1),
+            "--size" => config.size = value(&mut args, &arg)?,
+            "--plies" => config.plies = value(&mut args, &arg)?,
+            "--sprt" => config.sprt = Some((value(&mut args, &arg)?, value(&mut args, &arg)?)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ => engines.push(Engine::parse(&arg)?),
+        }
+    }
+    if !(4..255).contains(&config.size) || !config.size.is_multiple_of(2) {
+        return Err(format!("bad size: {}", config.size));
+    }
+    match engines.as_slice() {
+        &[first, second] => config.engines = (first, second),
+        _ => return Err("needs exactly two engines".to_string()),
+    }
+    Ok(config)
+}
+
+fn main() {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let (first, second) = config.engines;
+    let openings = openings(config.size, config.plies);
+    if openings.is_empty() {
+        eprintln!("no openings {} plies in", config.plies);
+        std::process::exit(2);
+    }
+    println!(
+        "{} vs {}: {} games from {} openings on {} threads",
+        first,
+        second,
+        config.games,
+        openings.len(),
+        config.threads
+    );
+
+    let next_game = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let mut tally = Tally::default();
+    let games = config.games;
+    thread::scope(|scope| {
+        for _ in 0..config.threads {
+            let (sender, next_game, stop, openings) =
+                (sender.clone(), &next_game, &stop, &openings);
+            scope.spawn(move || loop {
+                let game = next_game.fetch_add(1, Ordering::SeqCst);
+                if game >= games || stop.load(Ordering::SeqCst) {
+                    return;
+                }
+                // both colours of an opening are played back to back
+                let opening = &openings[game / 2 % openings.len()];
+                let first_is_black = game.is_multiple_of(2);
+                let result = play_game(first, second, opening, first_is_black, game as u64);
+                if sender.send((game, first_is_black, result)).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(sender);
+        for (game, first_is_black, (outcome, own, other)) in receiver.iter() {
+            tally.add(outcome);
+            let colour = if first_is_black { "black" } else { "white" };
+            println!(
+                "game {:>4}: {} as {} {}-{}. {}",
+                game + 1,
+                first,
+                colour,
+                own,
+                other,
+                tally
+            );
+            if let Some((elo0, elo1)) = config.sprt {
+                let llr = tally.llr(elo0, elo1);
+                let (lower, upper) = ((BETA / (1.0 - ALPHA)).ln(), ((1.0 - BETA) / ALPHA).ln());
+                if llr >= upper || llr <= lower {
+                    let accepted = if llr >= upper { elo1 } else { elo0 };
+                    println!(
+                        "SPRT: llr {:.2} crossed ({:.2}, {:.2}). accepted elo {}",
+                        llr, lower, upper, accepted
+                    );
+                    stop.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+        }
+    });
+    println!("{} vs {}: {}", first, second, tally);
+    if let Some((elo0, elo1)) = config.sprt {
+        println!(
+            "llr {:.2} for elo {} vs {}",
+            tally.llr(elo0, elo1),
+            elo0,
+            elo1
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn engines() {
+        assert_eq!(
+            Engine::parse("ab:3:discs"),
+            Ok(Engine::AlphaBeta {
+                strength: 3,
+                evaluator: Evaluator::Discs
+            })
+        );
+        assert_eq!(
+            Engine::parse("mcts:500"),
+            Ok(Engine::Mcts { iterations: 500 })
+        );
+        assert!(Engine::parse("ab:11").is_err());
+        assert!(Engine::parse("ab:0").is_err());
+        assert!(Engine::parse("ab:3:mobility").is_err());
+    }
+    #[test]
+    fn elo() {
+        let even = Tally {
+            wins: 10,
+            draws: 0,
+            losses: 10,
+        };
+        let (elo, margin) = even.elo().unwrap();
+        assert!(elo.abs() < 1e-9);
+        assert!(margin > 100.0, "20 games say very little");
+        let three_quarters = Tally {
+            wins: 75,
+            draws: 0,
+            losses: 25,
+        };
+        let (elo, _) = three_quarters.elo().unwrap();
+        assert!((elo - 190.85).abs() < 0.1, "{}", elo);
+        assert_eq!(
+            Tally {
+                wins: 3,
+                draws: 0,
+                losses: 0
+            }
+            .elo(),
+            None
+        );
+    }
+    #[test]
+    fn sprt() {
+        let winning = Tally {
+            wins: 300,
+            draws: 50,
+            losses: 150,
+        };
+        let upper = ((1.0 - BETA) / ALPHA).ln();
+        assert!(winning.llr(0.0, 20.0) > upper);
+        let even = Tally {
+            wins: 250,
+            draws: 50,
+            losses: 250,
+        };
+        assert!(even.llr(0.0, 20.0) < 0.0);
+    }
+    #[test]
+    fn openings_are_balanced_and_unique() {
+        let openings = openings(6, 2);
+        assert!(!openings.is_empty());
+        for (i, opening) in openings.iter().enumerate() {
+            assert!(!openings[..i].contains(opening));
+            assert_eq!(opening.1, Piece::Black);
+        }
+    }
+    #[test]
+    fn games_finish() {
+        let opening = openings(4, 0).remove(0);
+        let strong = Engine::parse("ab:2").unwrap();
+        let random = Engine::parse("mcts:1").unwrap();
+        for first_is_black in [true, false].iter().copied() {
+            let (_, own, other) = play_game(strong, random, &opening, first_is_black, 7);
+            assert!(own + other <= 16);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! an engine for the NBoard GUI, speaking its line protocol over stdin / stdout.
+//!
+//! add it in NBoard as an engine with this binary as the command. supported commands:
+//!
+//! - `nboard <version>`: answered with our name.
+//! - `set game <ggf>`, `move <move>[/eval/time]`: follow the game. see `boardgame_ai::ggf`.
+//! - `set depth <n>`: used as the strength (capped to 1..=10), like the strength slider of the web version.
+//! - `hint <n>`: the best `n` moves as `search` lines. evals are the evaluator's own units, not discs,
+//!   except that wins and losses show as +64 / -64.
+//! - `go`: the move to play, as `=== <move>`, after `nodestats <nodes> <seconds>` for the search.
+//!   the move isn't played until NBoard sends it back with `move`. the whole search report goes to stderr.
+//! - `ping <n>`: `pong <n>`, once everything before it is done. as we search synchronously, that's right away.
+//! - `learn`: we don't have a book, so it's just acknowledged.
+//!
+//! anything else is ignored, with a note on stderr.
+use boardgame_ai::ai::othello as ai;
+use boardgame_ai::ai::search::WIN_SCORE;
+use boardgame_ai::ggf::GgfGame;
+use boardgame_ai::rules::othello::{Board, Piece, Point};
+use std::io::{self, BufRead, Write};
+
+const NAME: &str = "boardgame-ai";
+
+struct Engine {
+    board: Board,
+    next: Piece,
+    strength: usize,
+}
+
+impl Engine {
+    fn new() -> Self {
+        // NBoard always sends the game before asking for anything
+        Engine {
+            board: Board::new(8, 8),
+            next: Piece::Black,
+            strength: 5,
+        }
+    }
+
+    fn handle(&mut self, line: &str, out: &mut impl Write) -> io::Result<()> {
+        let line = line.trim();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        let args = args.trim();
+        match command {
+            "nboard" => writeln!(out, "set myname {}", NAME)?,
+            "set" => self.set(args),
+            "move" => self.play(args),
+            "hint" => self.hint(args.parse().unwrap_or(1), out)?,
+            "go" => {
+                writeln!(out, "status thinking")?;
+                let (play, report) =
+                    ai::predict_report(&self.board, self.next, self.strength, self.strength);
+                writeln!(out, "status")?;
+                writeln!(
+                    out,
+                    "nodestats {} {:.3}",
+                    report.nodes,
+                    report.elapsed_ms / 1000.0
+                )?;
+                eprintln!("{}", report.summary());
+                writeln!(out, "=== {}", notation(play))?;
+            }
+            "ping" => writeln!(out, "pong {}", args)?,
+            "learn" => writeln!(out, "learned")?,
+            "" => {}
+            _ => eprintln!("ignored: {}", line),
+        }
+        out.flush()
+    }
+
+    fn set(&mut self, args: &str) {
+        let (key, value) = args.split_once(' ').unwrap_or((args, ""));
+        match key {
+            "game" => match GgfGame::parse(value).and_then(|game| game.position()) {
+                Ok((board, next)) => {
+                    self.board = board;
+                    self.next = next;
+                }
+                Err(error) => eprintln!("{}", String::from(error)),
+            },
+            "depth" => match value.trim().parse::<usize>() {
+                Ok(depth) => self.strength = depth.clamp(1, 10),
+                Err(_) => eprintln!("bad depth: {}", value),
+            },
+            _ => eprintln!("ignored: set {}", args),
+        }
+    }
+
+    fn play(&mut self, args: &str) {
+        let notation = args.split('/').next().unwrap_or_default().trim();
+        if notation.eq_ignore_ascii_case("pa") {
+            self.next = self.next.flip();
+            return;
+        }
+        let Some(at) = Point::from_notation(notation) else {
+            eprintln!("bad move: {}", args);
+            return;
+        };
+        match self.board.clone().place(at, self.next) {
+            Ok(board) => {
+                self.board = board;
+                self.next = self.next.flip();
+            }
+            Err(error) => eprintln!("{}", String::from(error)),
+        }
+    }
+
+    fn hint(&self, count: usize, out: &mut impl Write) -> io::Result<()> {
+        writeln!(out, "status thinking")?;
+        let ranked = ai::rank(&self.board, self.next, self.strength, self.strength);
+        let depth = ai::plies(self.strength);
+        for (play, score) in ranked.into_iter().take(count) {
+            let eval = if score >= WIN_SCORE {
+                64
+            } else if score <= -WIN_SCORE {
+                -64
+            } else {
+                score
+            };
+            writeln!(out, "search {} {} 0 {}", notation(play), eval, depth)?;
+        }
+        writeln!(out, "status")
+    }
+}
+
+fn notation(play: Option<Point>) -> String {
+    play.map_or("PA".to_string(), |at| at.notation().to_uppercase())
+}
+
+fn main() -> io::Result<()> {
+    let mut engine = Engine::new();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        engine.handle(&line?, &mut out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn run(engine: &mut Engine, lines: &[&str]) -> Vec<String> {
+        let mut out = Vec::new();
+        for line in lines {
+            engine.handle(line, &mut out).unwrap();
+        }
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+    const START: &str = "set game (;GM[Othello]PC[NBoard]PB[me]PW[you]RE[?]TI[5:00]TY[8]BO[8 ---------------------------O*------*O--------------------------- *];)";
+
+    #[test]
+    fn handshake() {
+        let mut engine = Engine::new();
+        let out = run(&mut engine, &["nboard 2", "set depth 3", "ping 1", "learn"]);
+        assert_eq!(out, vec!["set myname boardgame-ai", "pong 1", "learned"]);
+        assert_eq!(engine.strength, 3);
+    }
+    #[test]
+    fn follows_the_game() {
+        let mut engine = Engine::new();
+        run(&mut engine, &[START, "move F5/0.00/1.5", "move f6"]);
+        assert_eq!(engine.next, Piece::Black);
+        assert_eq!(engine.board.score(Piece::Black), 3);
+        // illegal moves are ignored
+        run(&mut engine, &["move a1"]);
+        assert_eq!(engine.next, Piece::Black);
+        let out = run(&mut engine, &["set depth 2", "go"]);
+        let stats = &out[out.len() - 2];
+        assert!(stats.starts_with("nodestats "), "{}", stats);
+        let play = out.last().unwrap().strip_prefix("=== ").unwrap();
+        let at = Point::from_notation(play).unwrap();
+        assert!(engine.board.count_flips(at, Piece::Black) > 0);
+        // nothing changes until NBoard sends the move back
+        assert_eq!(engine.next, Piece::Black);
+    }
+    #[test]
+    fn hint() {
+        let mut engine = Engine::new();
+        let out = run(&mut engine, &[START, "set depth 2", "hint 3"]);
+        assert_eq!(out.first().unwrap(), "status thinking");
+        assert_eq!(out.last().unwrap(), "status");
+        let searches: Vec<_> = out.iter().filter(|l| l.starts_with("search ")).collect();
+        // there are only four moves at the start
+        assert_eq!(searches.len(), 3);
+        assert!(searches.iter().all(|l| l.ends_with(" 0 2")));
+    }
+    #[test]
+    fn passes() {
+        // white has no move, but black does
+        let game = "set game (;GM[Othello]TY[4]BO[4 ----*O---------- O];)";
+        let mut engine = Engine::new();
+        let out = run(&mut engine, &[game, "go"]);
+        assert_eq!(out.last().unwrap(), "=== PA");
+        run(&mut engine, &["move PA"]);
+        assert_eq!(engine.next, Piece::Black);
+    }
+}
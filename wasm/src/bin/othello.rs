@@ -0,0 +1,288 @@
+//! play othello against the AI in a terminal.
+//!
+//! ```sh
+//! cargo run --release --bin othello -- --size 8 --strength 5 --color black
+//! ```
+//!
+//! moves are in standard notation (`f5`). the other commands are `pass` (only when there's no move),
+//! `undo` (takes back your last move and the AI's answer), `save <file>` (writes the game as GGF,
+//! which NBoard can open) and `quit`.
+use boardgame_ai::ai::othello as ai;
+use boardgame_ai::ggf::{GgfGame, GgfMove};
+use boardgame_ai::rules::othello::{Board, Piece, Point};
+use std::io::{self, BufRead, Write};
+
+const USAGE: &str =
+    "usage: othello [--size <even number, 4..=26>] [--strength <1..=10>] [--color <black|white>]";
+const HELP: &str =
+    "enter a move like f5, or one of: pass, undo, save <file>, quit. legal moves are marked with *.";
+
+struct Session {
+    game: GgfGame,
+    human: Piece,
+    strength: usize,
+}
+
+impl Session {
+    fn new(size: usize, strength: usize, human: Piece) -> Self {
+        let mut game = GgfGame::new(Board::new(size, size), Piece::Black);
+        match human {
+            Piece::Black => game.black = "you".to_string(),
+            Piece::White => game.white = "you".to_string(),
+        }
+        let ai_name = format!("boardgame-ai (strength {})", strength);
+        match human {
+            Piece::Black => game.white = ai_name,
+            Piece::White => game.black = ai_name,
+        }
+        Session {
+            game,
+            human,
+            strength,
+        }
+    }
+
+    fn position(&self) -> (Board, Piece) {
+        // every move went through `Board::place` before it was recorded
+        self.game.position().expect("this shouldn't happen")
+    }
+
+    fn push(&mut self, player: Piece, at: Option<Point>) {
+        self.game.moves.push(GgfMove { player, at });
+        let (board, _) = self.position();
+        if board.is_over() {
+            let (black, white) = (board.score(Piece::Black), board.score(Piece::White));
+            self.game.result = format!("{:+}", black as isize - white as isize);
+        }
+    }
+
+    /// plays the AI's moves until it's the human's turn or the game is over.
+    fn ai_moves(&mut self, out: &mut impl Write) -> io::Result<()> {
+        loop {
+            let (board, next) = self.position();
+            if board.is_over() || next == self.human {
+                return Ok(());
+            }
+            let (play, report) = ai::predict_report(&board, next, self.strength, self.strength);
+            match play {
+                Some(at) => writeln!(out, "ai plays {} ({})", at.notation(), report.summary())?,
+                None => writeln!(out, "ai passes")?,
+            }
+            self.push(next, play);
+        }
+    }
+
+    /// takes back moves up to and including the human's last one.
+    fn undo(&mut self) -> bool {
+        let Some(last) = self.game.moves.iter().rposition(|m| m.player == self.human) else {
+            return false;
+        };
+        self.game.moves.truncate(last);
+        self.game.result = "?".to_string();
+        true
+    }
+
+    fn command(&mut self, line: &str, out: &mut impl Write) -> io::Result<bool> {
+        let (board, next) = self.position();
+        let (command, args) = line.split_once(' ').unwrap_or((line, ""));
+        match command {
+            "" => {}
+            "quit" | "exit" => return Ok(false),
+            "help" => writeln!(out, "{}", HELP)?,
+            "undo" => {
+                if !self.undo() {
+                    writeln!(out, "nothing to undo")?;
+                }
+            }
+            "save" => {
+                let path = args.trim();
+                if path.is_empty() {
+                    writeln!(out, "usage: save <file>")?;
+                } else {
+                    match std::fs::write(path, format!("{}\n", self.game)) {
+                        Ok(()) => writeln!(out, "saved to {}", path)?,
+                        Err(error) => writeln!(out, "couldn't save: {}", error)?,
+                    }
+                }
+            }
+            _ if board.is_over() => writeln!(out, "the game is over. undo, save or quit")?,
+            "pass" => {
+                if board.placeable(next).is_empty() {
+                    self.push(next, None);
+                } else {
+                    writeln!(out, "you can't pass while you have a move")?;
+                }
+            }
+            _ => match Point::from_notation(command) {
+                Some(at) if board.count_flips(at, next) > 0 => self.push(next, Some(at)),
+                Some(_) => writeln!(out, "{} is not a legal move", command)?,
+                None => writeln!(out, "unknown command. {}", HELP)?,
+            },
+        }
+        Ok(true)
+    }
+
+    fn status(&self) -> String {
+        let (board, next) = self.position();
+        let (black, white) = (board.score(Piece::Black), board.score(Piece::White));
+        let score = format!("black {} - {} white", black, white);
+        if !board.is_over() {
+            let turn = if board.placeable(next).is_empty() {
+                "you have to pass"
+            } else {
+                "your move"
+            };
+            return format!("{}. {}", score, turn);
+        }
+        let result = match board.winner() {
+            None => "draw",
+            Some(winner) if winner == self.human => "you win",
+            Some(_) => "you lose",
+        };
+        format!("{}. game over, {}", score, result)
+    }
+}
+
+/// `board` with coordinates around it, and `*` on the cells `next` can place on.
+/// ```text
+///    abcd
+///  1 ..*.
+///  2 .bw*
+/// ```
+fn render(board: &Board, next: Piece) -> String {
+    let legal = board.placeable(next);
+    let encoded = board.encode();
+    let columns: String = (0..board.width())
+        .map(|x| Point::new(x, 0).notation().chars().next().unwrap_or(' '))
+        .collect();
+    let mut rendered = format!("   {}\n", columns);
+    for (y, row) in encoded.lines().filter(|row| !row.is_empty()).enumerate() {
+        let row: String = row
+            .chars()
+            .enumerate()
+            .map(|(x, cell)| {
+                if legal.contains(&Point::new(x, y)) {
+                    '*'
+                } else {
+                    cell
+                }
+            })
+            .collect();
+        rendered += &format!("{:>2} {}\n", y + 1, row);
+    }
+    rendered
+}
+
+fn play(session: &mut Session, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "{}", HELP)?;
+    session.ai_moves(out)?;
+    let mut lines = input.lines();
+    loop {
+        let (board, next) = session.position();
+        write!(out, "{}", render(&board, next))?;
+        writeln!(out, "{}", session.status())?;
+        write!(out, "> ")?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        if !session.command(line?.trim(), out)? {
+            return Ok(());
+        }
+        session.ai_moves(out)?;
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Session, String> {
+    let (mut size, mut strength, mut human) = (8, 5, Piece::Black);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?;
+        match arg.as_str() {
+            "--size" => {
+                size = value
+                    .parse()
+                    .ok()
+                    .filter(|size: &usize| (4..=26).contains(size) && size.is_multiple_of(2))
+                    .ok_or_else(|| format!("bad size: {}", value))?
+            }
+            "--strength" => {
+                strength = value
+                    .parse()
+                    .ok()
+                    .filter(|strength| (1..=10).contains(strength))
+                    .ok_or_else(|| format!("bad strength: {}", value))?
+            }
+            "--color" => {
+                human = match value.as_str() {
+                    "black" | "b" => Piece::Black,
+                    "white" | "w" => Piece::White,
+                    _ => return Err(format!("bad color: {}", value)),
+                }
+            }
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+    Ok(Session::new(size, strength, human))
+}
+
+fn main() -> io::Result<()> {
+    let mut session = match parse_args(std::env::args().skip(1)) {
+        Ok(session) => session,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let stdout = io::stdout();
+    play(&mut session, io::stdin().lock(), &mut stdout.lock())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+        args.split_whitespace().map(|arg| arg.to_string())
+    }
+    #[test]
+    fn render_marks_legal_moves() {
+        let board = Board::new(4, 4);
+        let expected = "   abcd\n 1 ..*.\n 2 .bw*\n 3 *wb.\n 4 .*..\n";
+        assert_eq!(render(&board, Piece::Black), expected);
+    }
+    #[test]
+    fn arguments() {
+        let session = parse_args(args("--size 6 --strength 3 --color white")).unwrap();
+        assert_eq!(session.human, Piece::White);
+        assert_eq!(session.strength, 3);
+        assert_eq!(session.game.board.width(), 6);
+        assert!(parse_args(args("--size 7")).is_err());
+        assert!(parse_args(args("--strength 11")).is_err());
+        assert!(parse_args(args("--size")).is_err());
+    }
+    #[test]
+    fn play_and_undo() {
+        let mut session = parse_args(args("--size 6 --strength 1")).unwrap();
+        let mut out = Vec::new();
+        let (board, next) = session.position();
+        let first = board.placeable(next)[0].notation();
+        let input = format!("z9\n{}\nundo\n", first);
+        play(&mut session, input.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("z9 is not a legal move"));
+        assert!(out.contains("ai plays"));
+        assert!(out.contains("nodes"));
+        // undo took back both moves
+        assert!(session.game.moves.is_empty());
+    }
+    #[test]
+    fn ai_moves_first_as_black() {
+        let mut session = parse_args(args("--size 4 --strength 1 --color white")).unwrap();
+        let mut out = Vec::new();
+        play(&mut session, "pass\nquit\n".as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(session.game.moves[0].player, Piece::Black);
+        assert!(out.contains("you can't pass while you have a move"));
+    }
+}
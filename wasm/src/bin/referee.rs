@@ -0,0 +1,144 @@
+//! referees othello games between two bots. see `boardgame_ai::referee` for the protocol.
+//!
+//! ```sh
+//! cargo run --release --bin referee -- "python3 my_bot.py" ai:3 --games 4 --time-ms 1000 --record games.ggf
+//! ```
+//!
+//! a bot is a command, or `ai:<strength>` for the built-in AI. with more than one game,
+//! the bots swap colours every game. every game is appended to the `--record` file as a line of GGF.
+use boardgame_ai::referee::{self, Bot};
+use boardgame_ai::rules::othello::{Board, Piece};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::time::Duration;
+
+const USAGE: &str =
+    "usage: referee <bot> <bot> [--size <n>] [--time-ms <n>] [--games <n>] [--record <file>]
+a bot is a command, or ai:<strength> for the built-in AI";
+
+struct Config {
+    bots: (Bot, Bot),
+    size: usize,
+    limit: Duration,
+    games: usize,
+    record: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+    let mut bots = Vec::new();
+    let (mut size, mut limit, mut games, mut record) = (8, 1000, 1, None);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        let number = |value: String| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("bad number: {}", value))
+        };
+        match arg.as_str() {
+            "--size" => size = number(value()?)?,
+            "--time-ms" => limit = number(value()?)?,
+            "--games" => games = number(value()?)?,
+            "--record" => record = Some(value()?),
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ => bots.push(Bot::parse(&arg)),
+        }
+    }
+    if !(4..255).contains(&size) || !size.is_multiple_of(2) {
+        return Err(format!("bad size: {}", size));
+    }
+    let bots = match bots.as_slice() {
+        [first, second] => (first.clone(), second.clone()),
+        _ => return Err("needs exactly two bots".to_string()),
+    };
+    Ok(Config {
+        bots,
+        size,
+        limit: Duration::from_millis(limit as u64),
+        games,
+        record,
+    })
+}
+
+fn main() {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let (first, second) = &config.bots;
+    // wins of the first and the second bot, and draws
+    let mut tally = (0, 0, 0);
+    for game in 0..config.games {
+        let first_is_black = game.is_multiple_of(2);
+        let (black, white) = if first_is_black {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let board = Board::new(config.size, config.size);
+        let result = referee::play(black, white, board, config.limit);
+        let first_piece = if first_is_black {
+            Piece::Black
+        } else {
+            Piece::White
+        };
+        match result.winner() {
+            Some(winner) if winner == first_piece => tally.0 += 1,
+            Some(_) => tally.1 += 1,
+            None => tally.2 += 1,
+        }
+        print!(
+            "game {}: {} (black) {}-{} {} (white)",
+            game + 1,
+            black.name(),
+            result.black,
+            result.white,
+            white.name()
+        );
+        match &result.forfeit {
+            Some((piece, reason)) => println!(", {:?} forfeited: {:?}", piece, reason),
+            None => println!(),
+        }
+        if let Some(path) = &config.record {
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", result.record));
+            if let Err(error) = written {
+                eprintln!("couldn't write {}: {}", path, error);
+            }
+        }
+    }
+    println!(
+        "{} {} - {} {}, {} draws",
+        first.name(),
+        tally.0,
+        tally.1,
+        second.name(),
+        tally.2
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+    #[test]
+    fn arguments() {
+        let config = parse_args(args(&["./bot --fast", "ai:2", "--time-ms", "50"])).unwrap();
+        assert_eq!(config.bots.0.name(), "./bot --fast");
+        assert_eq!(config.bots.1, Bot::Ai { strength: 2 });
+        assert_eq!(config.limit, Duration::from_millis(50));
+        assert!(parse_args(args(&["ai:1"])).is_err());
+        assert!(parse_args(args(&["ai:1", "ai:2", "--size", "5"])).is_err());
+        assert!(parse_args(args(&["ai:1", "ai:2", "--games"])).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! runs a round-robin or swiss tournament between bots, saving it after every game so it can be resumed.
+//!
+//! ```sh
+//! cargo run --release --bin tournament -- games.json --format swiss --rounds 5 \
+//!     --bot "mine=python3 my_bot.py" --bot weak=ai:2 --bot strong=ai:6 --html crosstable.html
+//! ```
+//!
+//! a bot is `<name>=<command>`, with `ai:<strength>` as the command for the built-in AI.
+//! if the file exists, the tournament in it is resumed and the options defining one are ignored.
+//! see `boardgame_ai::referee` for how bots are talked to.
+use boardgame_ai::referee;
+use boardgame_ai::rules::othello::Board;
+use boardgame_ai::tournament::{Entrant, Format, Tournament};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+
+const USAGE: &str = "usage: tournament <file.json> [--format round-robin|swiss] [--rounds <n>] [--size <n>] [--time-ms <n>]
+                  [--bot <name>=<command> ...] [--html <file>] [--record <file>]
+a command is ai:<strength> for the built-in AI";
+
+#[derive(Debug)]
+struct Config {
+    path: String,
+    format: Format,
+    size: usize,
+    time_ms: usize,
+    entrants: Vec<Entrant>,
+    html: Option<String>,
+    record: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+    let (mut path, mut format, mut rounds) = (None, "round-robin".to_string(), None);
+    let (mut size, mut time_ms, mut entrants) = (8, 1000, Vec::new());
+    let (mut html, mut record) = (None, None);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        let number = |value: String| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("bad number: {}", value))
+        };
+        match arg.as_str() {
+            "--format" => format = value()?,
+            "--rounds" => rounds = Some(number(value()?)?),
+            "--size" => size = number(value()?)?,
+            "--time-ms" => time_ms = number(value()?)?,
+            "--bot" => {
+                let bot = value()?;
+                let (name, command) = bot
+                    .split_once('=')
+                    .ok_or_else(|| format!("bad bot, should be <name>=<command>: {}", bot))?;
+                entrants.push(Entrant {
+                    name: name.to_string(),
+                    bot: command.to_string(),
+                });
+            }
+            "--html" => html = Some(value()?),
+            "--record" => record = Some(value()?),
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {}", arg)),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    let format = match (format.as_str(), rounds) {
+        ("round-robin", None) => Format::RoundRobin,
+        ("swiss", Some(rounds)) => Format::Swiss { rounds },
+        ("swiss", None) => return Err("swiss needs --rounds".to_string()),
+        ("round-robin", Some(_)) => return Err("--rounds is only for swiss".to_string()),
+        _ => return Err(format!("unknown format: {}", format)),
+    };
+    if !(4..255).contains(&size) || !size.is_multiple_of(2) {
+        return Err(format!("bad size: {}", size));
+    }
+    Ok(Config {
+        path: path.ok_or("needs a file")?,
+        format,
+        size,
+        time_ms,
+        entrants,
+        html,
+        record,
+    })
+}
+
+fn load(config: &Config) -> Result<Tournament, String> {
+    match fs::read_to_string(&config.path) {
+        Ok(text) => {
+            let tournament = Tournament::from_json(&text)
+                .map_err(|error| format!("couldn't read {}: {}", config.path, error))?;
+            let played = tournament.pairings.iter().filter(|p| p.is_played()).count();
+            println!("resuming {}, {} games played", config.path, played);
+            Ok(tournament)
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            if config.entrants.len() < 2 {
+                return Err("needs at least two bots".to_string());
+            }
+            Ok(Tournament::new(
+                config.format,
+                config.size,
+                config.time_ms,
+                config.entrants.clone(),
+            ))
+        }
+        Err(error) => Err(format!("couldn't read {}: {}", config.path, error)),
+    }
+}
+
+fn print_standings(tournament: &Tournament) {
+    println!("  # name                 points   discs  buchholz");
+    for (rank, standing) in tournament.standings().iter().enumerate() {
+        println!(
+            "{:>3} {:<20} {:>6} {:>7} {:>9}",
+            rank + 1,
+            tournament.entrants[standing.entrant].name,
+            standing.points,
+            format!("{:+}", standing.disc_differential),
+            standing.buchholz
+        );
+    }
+}
+
+fn main() {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let mut tournament = match load(&config) {
+        Ok(tournament) => tournament,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    let limit = Duration::from_millis(tournament.time_ms as u64);
+    while let Some(game) = tournament.next_game() {
+        let pairing = tournament.pairings[game];
+        let white = pairing.white.expect("byes are never unplayed");
+        let (black, white) = (
+            &tournament.entrants[pairing.black],
+            &tournament.entrants[white],
+        );
+        let board = Board::new(tournament.size, tournament.size);
+        let result = referee::play(&black.bot(), &white.bot(), board, limit);
+        print!(
+            "round {}: {} (black) {}-{} {} (white)",
+            pairing.round, black.name, result.black, result.white, white.name
+        );
+        match &result.forfeit {
+            Some((piece, reason)) => println!(", {:?} forfeited: {:?}", piece, reason),
+            None => println!(),
+        }
+        tournament.record(game, result.black, result.white);
+        if let Err(error) = fs::write(&config.path, tournament.to_json()) {
+            eprintln!("couldn't write {}: {}", config.path, error);
+            std::process::exit(1);
+        }
+        if let Some(path) = &config.record {
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| writeln!(file, "{}", result.record));
+            if let Err(error) = written {
+                eprintln!("couldn't write {}: {}", path, error);
+            }
+        }
+    }
+    print_standings(&tournament);
+    if let Some(path) = &config.html {
+        if let Err(error) = fs::write(path, tournament.crosstable_html()) {
+            eprintln!("couldn't write {}: {}", path, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+    #[test]
+    fn arguments() {
+        let config = parse_args(args(&[
+            "t.json",
+            "--format",
+            "swiss",
+            "--rounds",
+            "4",
+            "--bot",
+            "mine=./bot --depth=3",
+            "--bot",
+            "weak=ai:1",
+        ]))
+        .unwrap();
+        assert_eq!(config.path, "t.json");
+        assert_eq!(config.format, Format::Swiss { rounds: 4 });
+        assert_eq!(config.entrants[0].name, "mine");
+        assert_eq!(config.entrants[0].bot, "./bot --depth=3");
+        assert!(parse_args(args(&["t.json", "--format", "swiss"])).is_err());
+        assert!(parse_args(args(&["t.json", "--bot", "no-name"])).is_err());
+        assert!(parse_args(args(&["--size", "8"])).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! othello game records in GGF (generic game format), as used by GGS, NBoard and Edax.
+//!
+//! a record looks like `(;GM[Othello]PB[black]PW[white]RE[+2]TY[8]BO[8 <cells> *]B[f5]W[f6//1.2];)`.
+//! `BO` is the starting position: the board size, one character per cell row by row
+//! (`-` empty, `*` black, `O` white), and who moves first. the cells are either one word,
+//! or a word per row as GGS writes them. moves may carry `/eval/time` after them,
+//! and a pass is `PA`. tags other than the ones below are skipped when reading.
+use crate::rules::othello::{Board, Cell, Piece, Point};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GgfMove {
+    pub player: Piece,
+    /// None is a pass.
+    pub at: Option<Point>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GgfGame {
+    /// the starting position.
+    pub board: Board,
+    /// who moves first from `board`.
+    pub first: Piece,
+    pub black: String,
+    pub white: String,
+    /// `?` while the game isn't over.
+    pub result: String,
+    pub moves: Vec<GgfMove>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GgfError {
+    /// not wrapped in `(;` and `;)`, or a tag isn't closed.
+    Malformed,
+    MissingBoard,
+    BadBoard(String),
+    BadMove(String),
+    /// a move that can't be played in the position it's played in.
+    IllegalMove(GgfMove),
+}
+impl From<GgfError> for String {
+    fn from(error: GgfError) -> String {
+        format!("Failed to read GGF. error: {error:?}")
+    }
+}
+
+impl GgfGame {
+    /// a game without moves yet, with unknown players.
+    pub fn new(board: Board, first: Piece) -> Self {
+        GgfGame {
+            board,
+            first,
+            black: "?".to_string(),
+            white: "?".to_string(),
+            result: "?".to_string(),
+            moves: Vec::new(),
+        }
+    }
+
+    /// the position after every move, and who moves next.
+    /// ```rust
+    /// use boardgame_ai::ggf::GgfGame;
+    /// use boardgame_ai::rules::othello::*;
+    /// let record = "(;GM[Othello]PB[a]PW[b]RE[?]TY[8]BO[8 ---------------------------O*------*O--------------------------- *]B[F5//0.01]W[f6];)";
+    /// let game = GgfGame::parse(record).unwrap();
+    /// let (board, next) = game.position().unwrap();
+    /// assert_eq!(next, Piece::Black);
+    /// assert_eq!(board.get(Point::new(5, 5)), Ok(Cell::White));
+    /// assert_eq!(board.score(Piece::Black), 3);
+    /// ```
+    pub fn position(&self) -> Result<(Board, Piece), GgfError> {
+        let mut board = self.board.clone();
+        let mut next = self.first;
+        for &play in &self.moves {
+            if let Some(at) = play.at {
+                board = board
+                    .place(at, play.player)
+                    .map_err(|_| GgfError::IllegalMove(play))?;
+            }
+            next = play.player.flip();
+        }
+        Ok((board, next))
+    }
+
+    pub fn parse(record: &str) -> Result<GgfGame, GgfError> {
+        let body = record
+            .trim()
+            .strip_prefix("(;")
+            .and_then(|s| s.strip_suffix(";)"))
+            .ok_or(GgfError::Malformed)?;
+        let mut game: Option<GgfGame> = None;
+        let (mut black, mut white, mut result) = (None, None, None);
+        let mut moves = Vec::new();
+        let mut rest = body.trim_start();
+        while !rest.is_empty() {
+            let open = rest.find('[').ok_or(GgfError::Malformed)?;
+            let close = rest[open..].find(']').ok_or(GgfError::Malformed)? + open;
+            let (tag, value) = (rest[..open].trim(), &rest[open + 1..close]);
+            rest = rest[close + 1..].trim_start();
+            match tag {
+                "BO" => game = Some(parse_board(value)?),
+                "PB" => black = Some(value.to_string()),
+                "PW" => white = Some(value.to_string()),
+                "RE" => result = Some(value.to_string()),
+                "B" | "W" => {
+                    let player = if tag == "B" {
+                        Piece::Black
+                    } else {
+                        Piece::White
+                    };
+                    let at = parse_move(value)?;
+                    moves.push(GgfMove { player, at });
+                }
+                _ => {}
+            }
+        }
+        let mut game = game.ok_or(GgfError::MissingBoard)?;
+        game.black = black.unwrap_or(game.black);
+        game.white = white.unwrap_or(game.white);
+        game.result = result.unwrap_or(game.result);
+        game.moves = moves;
+        Ok(game)
+    }
+}
+
+// `8 <cells> *`, where GGS writes the cells a row per word
+fn parse_board(value: &str) -> Result<GgfGame, GgfError> {
+    let bad = || GgfError::BadBoard(value.to_string());
+    let parts: Vec<_> = value.split_whitespace().collect();
+    let [size, rows @ .., first] = parts.as_slice() else {
+        return Err(bad());
+    };
+    let size: usize = size.parse().map_err(|_| bad())?;
+    let cells: String = rows.concat();
+    let first = match *first {
+        "*" => Piece::Black,
+        "O" => Piece::White,
+        _ => return Err(bad()),
+    };
+    if size < 2 || !size.is_multiple_of(2) || size >= 255 || cells.chars().count() != size * size {
+        return Err(bad());
+    }
+    let mut board = Board::new(size, size);
+    for (i, char) in cells.chars().enumerate() {
+        let cell = match char {
+            '-' => Cell::Empty,
+            '*' => Cell::Black,
+            'O' => Cell::White,
+            _ => return Err(bad()),
+        };
+        board
+            .set(Point::new(i % size, i / size), cell)
+            .expect("this shouldn't happen");
+    }
+    Ok(GgfGame::new(board, first))
+}
+
+// `f5`, `F5/1.00/0.5` or `PA`
+fn parse_move(value: &str) -> Result<Option<Point>, GgfError> {
+    let notation = value.split('/').next().unwrap_or_default().trim();
+    if notation.eq_ignore_ascii_case("pa") || notation.eq_ignore_ascii_case("pass") {
+        return Ok(None);
+    }
+    Point::from_notation(notation)
+        .map(Some)
+        .ok_or_else(|| GgfError::BadMove(value.to_string()))
+}
+
+impl fmt::Display for GgfGame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let size = self.board.width();
+        let cells: String = self
+            .board
+            .clone()
+            .cells()
+            .into_iter()
+            .map(|(_, cell)| match cell {
+                Cell::Black => '*',
+                Cell::White => 'O',
+                _ => '-',
+            })
+            .collect();
+        let first = match self.first {
+            Piece::Black => '*',
+            Piece::White => 'O',
+        };
+        write!(
+            f,
+            "(;GM[Othello]PB[{}]PW[{}]RE[{}]TY[{}]BO[{} {} {}]",
+            self.black, self.white, self.result, size, size, cells, first
+        )?;
+        for play in &self.moves {
+            let tag = match play.player {
+                Piece::Black => "B",
+                Piece::White => "W",
+            };
+            let at = play
+                .at
+                .map_or("PA".to_string(), |at| at.notation().to_uppercase());
+            write!(f, "{}[{}]", tag, at)?;
+        }
+        write!(f, ";)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn round_trip() {
+        let mut game = GgfGame::new(Board::new(8, 8), Piece::Black);
+        game.black = "edax".to_string();
+        let mut suffix = String::new();
+        for _ in 0..3 {
+            let (board, next) = game.position().unwrap();
+            let at = board.placeable(next)[0];
+            suffix += &format!(
+                "{}[{}]",
+                if next == Piece::Black { "B" } else { "W" },
+                at.notation().to_uppercase()
+            );
+            game.moves.push(GgfMove {
+                player: next,
+                at: Some(at),
+            });
+        }
+        let record = game.to_string();
+        assert!(record.contains("PB[edax]"));
+        assert!(record.ends_with(&(suffix + ";)")));
+        assert_eq!(GgfGame::parse(&record), Ok(game));
+    }
+    #[test]
+    fn ggs_rows() {
+        let record = "(;GM[Othello]TY[8]BO[8 -------- -------- -------- ---*O--- ---O*--- -------- -------- -------- *]B[f5];)";
+        let game = GgfGame::parse(record).unwrap();
+        assert_eq!(game.board, Board::new(8, 8));
+        assert_eq!(game.first, Piece::Black);
+        assert_eq!(game.moves[0].at, Point::from_notation("f5"));
+    }
+    #[test]
+    fn passes_and_errors() {
+        let record = "(;GM[Othello]TY[4]BO[4 -----*O--O*----- O]W[PA]B[a1];)";
+        let game = GgfGame::parse(record).unwrap();
+        assert_eq!(game.first, Piece::White);
+        assert_eq!(game.moves[0].at, None);
+        // a1 flips nothing
+        assert!(matches!(game.position(), Err(GgfError::IllegalMove(_))));
+        assert_eq!(GgfGame::parse("GM[Othello]"), Err(GgfError::Malformed));
+        assert_eq!(
+            GgfGame::parse("(;GM[Othello];)"),
+            Err(GgfError::MissingBoard)
+        );
+        let bad = "(;BO[4 ---- *];)";
+        assert!(matches!(GgfGame::parse(bad), Err(GgfError::BadBoard(_))));
+    }
+}
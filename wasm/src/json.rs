@@ -0,0 +1,319 @@
+//! a small JSON value, for files and messages that Rust has to read back.
+//! (the wasm bindings only ever write JSON, so they just `format!` it and let `JSON.parse` do the rest.)
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// keys keep their order.
+    Object(Vec<(String, Json)>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JsonError {
+    UnexpectedEnd,
+    /// an unexpected character, and where it is in bytes.
+    UnexpectedChar(char, usize),
+    TrailingCharacters(usize),
+}
+impl From<JsonError> for String {
+    fn from(error: JsonError) -> String {
+        format!("Failed to parse JSON. error: {error:?}")
+    }
+}
+
+impl Json {
+    /// ```rust
+    /// use boardgame_ai::json::Json;
+    /// let json = Json::parse(r#"{"name": "ai", "strength": 5, "tags": [true, null]}"#).unwrap();
+    /// assert_eq!(json.get("name").and_then(Json::as_str), Some("ai"));
+    /// assert_eq!(json.get("strength").and_then(Json::as_usize), Some(5));
+    /// assert_eq!(json.to_string(), r#"{"name":"ai","strength":5,"tags":[true,null]}"#);
+    /// ```
+    pub fn parse(text: &str) -> Result<Json, JsonError> {
+        let mut parser = Parser { text, at: 0 };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.at < text.len() {
+            return Err(JsonError::TrailingCharacters(parser.at));
+        }
+        Ok(value)
+    }
+
+    /// the value of `key`, if this is an object that has it.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+    /// Some only for whole, non-negative numbers.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_f64()
+            .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+            .map(|n| n as usize)
+    }
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Json {
+        Json::String(s.to_string())
+    }
+}
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Number(n as f64)
+    }
+}
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+/// `s` as a JSON string literal, quotes included.
+pub fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            // JSON has no NaN nor infinity
+            Json::Number(n) if !n.is_finite() => write!(f, "null"),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write!(f, "{}", quote(s)),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", quote(key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    // in bytes
+    at: usize,
+}
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.at..].chars().next()
+    }
+    fn next(&mut self) -> Result<char, JsonError> {
+        let c = self.peek().ok_or(JsonError::UnexpectedEnd)?;
+        self.at += c.len_utf8();
+        Ok(c)
+    }
+    fn expect(&mut self, expected: char) -> Result<(), JsonError> {
+        let at = self.at;
+        match self.next()? {
+            c if c == expected => Ok(()),
+            c => Err(JsonError::UnexpectedChar(c, at)),
+        }
+    }
+    fn whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+            self.at += 1;
+        }
+    }
+    fn literal(&mut self, literal: &str, value: Json) -> Result<Json, JsonError> {
+        for expected in literal.chars() {
+            self.expect(expected)?;
+        }
+        Ok(value)
+    }
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.whitespace();
+        match self.peek().ok_or(JsonError::UnexpectedEnd)? {
+            'n' => self.literal("null", Json::Null),
+            't' => self.literal("true", Json::Bool(true)),
+            'f' => self.literal("false", Json::Bool(false)),
+            '"' => self.string().map(Json::String),
+            '[' => {
+                self.next()?;
+                let mut items = Vec::new();
+                self.whitespace();
+                if self.peek() == Some(']') {
+                    self.next()?;
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.whitespace();
+                    let at = self.at;
+                    match self.next()? {
+                        ',' => {}
+                        ']' => return Ok(Json::Array(items)),
+                        c => return Err(JsonError::UnexpectedChar(c, at)),
+                    }
+                }
+            }
+            '{' => {
+                self.next()?;
+                let mut entries = Vec::new();
+                self.whitespace();
+                if self.peek() == Some('}') {
+                    self.next()?;
+                    return Ok(Json::Object(entries));
+                }
+                loop {
+                    self.whitespace();
+                    let key = self.string()?;
+                    self.whitespace();
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    self.whitespace();
+                    let at = self.at;
+                    match self.next()? {
+                        ',' => {}
+                        '}' => return Ok(Json::Object(entries)),
+                        c => return Err(JsonError::UnexpectedChar(c, at)),
+                    }
+                }
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                let start = self.at;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(c))
+                {
+                    self.at += 1;
+                }
+                self.text[start..self.at]
+                    .parse()
+                    .map(Json::Number)
+                    .map_err(|_| JsonError::UnexpectedChar(c, start))
+            }
+            c => Err(JsonError::UnexpectedChar(c, self.at)),
+        }
+    }
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            let at = self.at;
+            match self.next()? {
+                '"' => return Ok(s),
+                '\\' => match self.next()? {
+                    'n' => s.push('\n'),
+                    'r' => s.push('\r'),
+                    't' => s.push('\t'),
+                    'b' => s.push('\u{8}'),
+                    'f' => s.push('\u{c}'),
+                    'u' => {
+                        let hex = self
+                            .text
+                            .get(self.at..self.at + 4)
+                            .ok_or(JsonError::UnexpectedEnd)?;
+                        let code = u32::from_str_radix(hex, 16)
+                            .map_err(|_| JsonError::UnexpectedChar('u', at))?;
+                        self.at += 4;
+                        // surrogate pairs aren't worth it here
+                        s.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    c => s.push(c), // `"`, `\` and `/`
+                },
+                c => s.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn round_trip() {
+        let text =
+            r#"{"a":[1,-2.5,1e3],"b":"quote \" backslash \\ newline \n tab \t","c":{},"d":[]}"#;
+        let json = Json::parse(text).unwrap();
+        assert_eq!(
+            json.get("a").and_then(Json::as_array).map(|a| a.len()),
+            Some(3)
+        );
+        assert_eq!(Json::parse(&json.to_string()), Ok(json));
+        assert_eq!(Json::parse(r#" "é" "#), Ok(Json::String("é".to_string())));
+    }
+    #[test]
+    fn errors() {
+        assert_eq!(Json::parse(""), Err(JsonError::UnexpectedEnd));
+        assert_eq!(Json::parse("[1,]"), Err(JsonError::UnexpectedChar(']', 3)));
+        assert_eq!(Json::parse("{} x"), Err(JsonError::TrailingCharacters(3)));
+        assert_eq!(Json::parse("nul"), Err(JsonError::UnexpectedEnd));
+        assert_eq!(
+            Json::parse(r#"{"a" 1}"#),
+            Err(JsonError::UnexpectedChar('1', 5))
+        );
+    }
+}
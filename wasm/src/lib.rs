@@ -1,9 +1,24 @@
 pub mod ai;
+pub mod ggf;
+pub mod json;
+pub mod referee;
 pub mod rules;
+pub mod session;
+pub mod tournament;
 mod utils;
+pub mod worker;
 
 use ai::othello::predict;
-use rules::othello::{self as othello_rules, Board, Piece, Point};
+use rules::ataxx::{AtaxxBoard, AtaxxMove};
+use rules::connect_four::{ConnectFourBoard, Disc};
+use rules::cube_othello::{CubeBoard, Point3};
+use rules::draughts::{DraughtsBoard, DraughtsVariant};
+use rules::gomoku::{GomokuBoard, GomokuRule};
+use rules::hex::HexGameBoard;
+use rules::hex_othello::{HexBoard, HexPoint};
+use rules::othello::{self as othello_rules, Board, OthelloError, Piece, Point};
+use rules::rolit::{Color, RolitBoard};
+use rules::GameError;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
@@ -22,8 +37,20 @@ pub fn version() -> String {
 }
 
 #[wasm_bindgen]
-pub fn init_othello(size: usize) -> Board {
-    othello_rules::Board::new(size)
+/// fails with `InvalidSize` unless both sides are even and within 2..255.
+pub fn init_othello(width: usize, height: usize) -> Result<Board, OthelloError> {
+    othello_rules::Board::try_new(width, height)
+}
+
+#[wasm_bindgen]
+/// starts from an arbitrary setup, e.g. puzzles or boards with blocked cells.
+/// see `Board::decode` for the format.
+pub fn init_othello_from(
+    serialized: &str,
+    width: usize,
+    height: usize,
+) -> Result<Board, OthelloError> {
+    Ok(othello_rules::Board::decode(serialized, width, height)?)
 }
 
 #[wasm_bindgen]
@@ -59,17 +86,24 @@ pub fn count_score(board: &Board) -> Scores {
 ///
 /// - the point is already occupied
 /// - the point is not placeable place
-pub fn place_at(board: &Board, player: Piece, at: &Point) -> Result<Board, String> {
+///
+/// the error's `kind`, `point` and `player` tell which.
+pub fn place_at(board: &Board, player: Piece, at: &Point) -> Result<Board, OthelloError> {
     let board = board.clone().place(*at, player)?;
     Ok(board)
 }
 
 #[wasm_bindgen]
-pub fn generate_ai_play(board: &Board, ai_player: Piece, strength: usize) -> Board {
+/// passes (returns the board as is) when the AI has no move.
+pub fn generate_ai_play(
+    board: &Board,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<Board, OthelloError> {
     let next_play = predict(board, ai_player, strength, strength);
     match next_play {
-        Some(play) => board.clone().place(play, ai_player).unwrap(),
-        None => board.clone(),
+        Some(play) => Ok(board.clone().place(play, ai_player)?),
+        None => Ok(board.clone()),
     }
 }
 
@@ -77,3 +111,339 @@ pub fn generate_ai_play(board: &Board, ai_player: Piece, strength: usize) -> Boa
 pub fn score(board: &Board, player: Piece) -> usize {
     board.score(player)
 }
+
+#[wasm_bindgen]
+pub fn init_rolit(width: usize, height: usize, players: usize) -> Result<RolitBoard, GameError> {
+    RolitBoard::try_new(width, height, players)
+}
+
+#[wasm_bindgen]
+pub fn rolit_placeable(board: &RolitBoard, player: Color) -> usize {
+    board.placeable(player).len()
+}
+#[wasm_bindgen]
+pub fn rolit_can_place(board: &RolitBoard, at: &Point, player: Color) -> bool {
+    board.placeable(player).contains(at)
+}
+#[wasm_bindgen]
+pub fn rolit_place_at(
+    board: &RolitBoard,
+    player: Color,
+    at: &Point,
+) -> Result<RolitBoard, GameError> {
+    let board = board.clone().place(*at, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// the player who plays after `player`.
+pub fn rolit_next_player(board: &RolitBoard, player: Color) -> Color {
+    player.next(board.players())
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 1..=4 plies, capped there, as each ply is a lot wider than othello's.
+pub fn rolit_generate_ai_play(
+    board: &RolitBoard,
+    ai_player: Color,
+    strength: usize,
+) -> Result<RolitBoard, GameError> {
+    let next_play = ai::rolit::predict(board, ai_player, 1 + strength.min(9) / 3);
+    match next_play {
+        Some(play) => Ok(board.clone().place(play, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_hex_othello(radius: usize) -> Result<HexBoard, GameError> {
+    HexBoard::try_new(radius)
+}
+
+#[wasm_bindgen]
+pub fn hex_placeable(board: &HexBoard, player: Piece) -> usize {
+    board.placeable(player).len()
+}
+#[wasm_bindgen]
+pub fn hex_can_place(board: &HexBoard, at: &HexPoint, player: Piece) -> bool {
+    board.count_flips(*at, player) > 0
+}
+#[wasm_bindgen]
+pub fn hex_place_at(board: &HexBoard, player: Piece, at: &HexPoint) -> Result<HexBoard, GameError> {
+    let board = board.clone().place(*at, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 1..=4 plies, capped there, as the search looks at every move.
+pub fn hex_generate_ai_play(
+    board: &HexBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<HexBoard, GameError> {
+    let next_play = ai::hex_othello::predict(board, ai_player, 1 + strength.min(9) / 3);
+    match next_play {
+        Some(play) => Ok(board.clone().place(play, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_cube_othello(size: usize) -> Result<CubeBoard, GameError> {
+    CubeBoard::try_new(size)
+}
+
+#[wasm_bindgen]
+pub fn cube_placeable(board: &CubeBoard, player: Piece) -> usize {
+    board.placeable(player).len()
+}
+#[wasm_bindgen]
+pub fn cube_can_place(board: &CubeBoard, at: &Point3, player: Piece) -> bool {
+    board.count_flips(*at, player) > 0
+}
+#[wasm_bindgen]
+pub fn cube_place_at(
+    board: &CubeBoard,
+    player: Piece,
+    at: &Point3,
+) -> Result<CubeBoard, GameError> {
+    let board = board.clone().place(*at, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 1..=3 plies, capped there, as the cube has a lot more legal moves than othello.
+pub fn cube_generate_ai_play(
+    board: &CubeBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<CubeBoard, GameError> {
+    let next_play = ai::cube_othello::predict(board, ai_player, 1 + strength.min(9) / 4);
+    match next_play {
+        Some(play) => Ok(board.clone().place(play, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_gravity_othello(width: usize, height: usize) -> Result<Board, OthelloError> {
+    rules::gravity_othello::try_new(width, height)
+}
+
+#[wasm_bindgen]
+pub fn gravity_placeable(board: &Board, player: Piece) -> usize {
+    rules::gravity_othello::placeable(board, player).len()
+}
+#[wasm_bindgen]
+pub fn gravity_can_place(board: &Board, column: usize, player: Piece) -> bool {
+    rules::gravity_othello::count_flips(board, column, player) > 0
+}
+#[wasm_bindgen]
+pub fn gravity_legal_mask(board: &Board, player: Piece) -> Vec<u8> {
+    rules::gravity_othello::legal_mask(board, player)
+}
+#[wasm_bindgen]
+pub fn gravity_place_at(
+    board: &Board,
+    player: Piece,
+    column: usize,
+) -> Result<Board, OthelloError> {
+    let board = rules::gravity_othello::place(board.clone(), column, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=10 is mapped to 1..=6 plies. see `ai::gravity_othello::plies`.
+pub fn gravity_generate_ai_play(
+    board: &Board,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<Board, OthelloError> {
+    let plies = ai::gravity_othello::plies(strength);
+    let next_play = ai::gravity_othello::predict(board, ai_player, plies);
+    match next_play {
+        Some(column) => Ok(rules::gravity_othello::place(
+            board.clone(),
+            column,
+            ai_player,
+        )?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_connect_four(
+    width: usize,
+    height: usize,
+    connect: usize,
+) -> Result<ConnectFourBoard, GameError> {
+    ConnectFourBoard::try_new(width, height, connect)
+}
+
+#[wasm_bindgen]
+pub fn connect_four_place_at(
+    board: &ConnectFourBoard,
+    player: Disc,
+    column: usize,
+) -> Result<ConnectFourBoard, GameError> {
+    let board = board.clone().place(column, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+pub fn connect_four_generate_ai_play(
+    board: &ConnectFourBoard,
+    ai_player: Disc,
+    strength: usize,
+) -> Result<ConnectFourBoard, GameError> {
+    let next_play = ai::connect_four::predict(board, ai_player, strength);
+    match next_play {
+        Some(column) => Ok(board.clone().place(column, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_gomoku(size: usize, rule: GomokuRule) -> Result<GomokuBoard, GameError> {
+    GomokuBoard::try_new(size, rule)
+}
+
+#[wasm_bindgen]
+pub fn gomoku_placeable(board: &GomokuBoard, player: Piece) -> usize {
+    board.placeable(player).len()
+}
+#[wasm_bindgen]
+/// false for occupied points, and for black's forbidden points in renju.
+pub fn gomoku_can_place(board: &GomokuBoard, at: &Point, player: Piece) -> bool {
+    board.get(*at) == Ok(othello_rules::Cell::Empty)
+        && (player == Piece::White || !board.is_forbidden(at))
+}
+#[wasm_bindgen]
+pub fn gomoku_place_at(
+    board: &GomokuBoard,
+    player: Piece,
+    at: &Point,
+) -> Result<GomokuBoard, GameError> {
+    let board = board.clone().place(*at, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+pub fn gomoku_generate_ai_play(
+    board: &GomokuBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<GomokuBoard, GameError> {
+    let next_play = ai::gomoku::predict(board, ai_player, strength);
+    match next_play {
+        Some(play) => Ok(board.clone().place(play, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_ataxx(width: usize, height: usize) -> Result<AtaxxBoard, GameError> {
+    AtaxxBoard::try_new(width, height)
+}
+#[wasm_bindgen]
+/// starts from an arbitrary setup, e.g. with blocked cells. see `AtaxxBoard::decode` for the format.
+pub fn init_ataxx_from(
+    serialized: &str,
+    width: usize,
+    height: usize,
+) -> Result<AtaxxBoard, GameError> {
+    Ok(AtaxxBoard::decode(serialized, width, height)?)
+}
+
+#[wasm_bindgen]
+pub fn ataxx_placeable(board: &AtaxxBoard, player: Piece) -> usize {
+    board.moves(player).len()
+}
+#[wasm_bindgen]
+pub fn ataxx_can_move(board: &AtaxxBoard, play: &AtaxxMove, player: Piece) -> bool {
+    board.clone().play(*play, player).is_ok()
+}
+#[wasm_bindgen]
+pub fn ataxx_move(
+    board: &AtaxxBoard,
+    player: Piece,
+    play: &AtaxxMove,
+) -> Result<AtaxxBoard, GameError> {
+    let board = board.clone().play(*play, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 1..=4 plies, capped there, over the 12 most promising moves, as ataxx has a lot of jumps.
+/// returns the board unchanged if `ai_player` has to pass.
+pub fn ataxx_generate_ai_play(
+    board: &AtaxxBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<AtaxxBoard, GameError> {
+    let next_play = ai::ataxx::predict(board, ai_player, 1 + strength.min(9) / 3, 12);
+    match next_play {
+        Some(play) => Ok(board.clone().play(play, ai_player)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_hex_game(size: usize, swap_rule: bool) -> Result<HexGameBoard, GameError> {
+    HexGameBoard::try_new(size, swap_rule)
+}
+
+#[wasm_bindgen]
+pub fn hex_game_can_place(board: &HexGameBoard, at: &Point, player: Piece) -> bool {
+    board.clone().place(*at, player).is_ok()
+}
+#[wasm_bindgen]
+pub fn hex_game_place_at(
+    board: &HexGameBoard,
+    player: Piece,
+    at: &Point,
+) -> Result<HexGameBoard, GameError> {
+    let board = board.clone().place(*at, player)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+pub fn hex_game_swap(board: &HexGameBoard) -> Result<HexGameBoard, GameError> {
+    let board = board.clone().swap()?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 300..=2700 playouts, capped there. the playouts are seeded with the board,
+/// so the same position always gets the same answer.
+pub fn hex_game_generate_ai_play(
+    board: &HexGameBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<HexGameBoard, GameError> {
+    let seed = board.points().fold(strength as u64, |seed, at| {
+        seed.wrapping_mul(3) + board.get(at).map_or(0, |cell| cell as u64)
+    });
+    let next_play = ai::hex::predict(board, ai_player, 300 * strength.clamp(1, 9), seed);
+    match next_play {
+        Some(play) => Ok(board.clone().play(play)?),
+        None => Ok(board.clone()),
+    }
+}
+
+#[wasm_bindgen]
+pub fn init_draughts(variant: DraughtsVariant) -> DraughtsBoard {
+    DraughtsBoard::new(variant)
+}
+
+#[wasm_bindgen]
+/// plays the move written as `notation`, like `11-15` or `15x22x29`. see `DraughtsBoard::legal_moves`.
+pub fn draughts_play(board: &DraughtsBoard, notation: &str) -> Result<DraughtsBoard, GameError> {
+    let play = board.parse_move(notation)?;
+    let board = board.clone().play(&play)?;
+    Ok(board)
+}
+#[wasm_bindgen]
+/// strength 1..=9 is mapped to 2..=6 plies, capped there.
+/// returns the board unchanged if it's not `ai_player`'s turn or the game is over.
+pub fn draughts_generate_ai_play(
+    board: &DraughtsBoard,
+    ai_player: Piece,
+    strength: usize,
+) -> Result<DraughtsBoard, GameError> {
+    let next_play = ai::draughts::predict(board, ai_player, 2 + strength.min(9) / 2);
+    match next_play {
+        Some(play) => Ok(board.clone().play(&play)?),
+        None => Ok(board.clone()),
+    }
+}
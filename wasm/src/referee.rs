@@ -0,0 +1,321 @@
+//! runs othello games between bots, which are separate programs talking over stdin / stdout.
+//!
+//! the protocol is one line per message. on its turn, a bot gets
+//!
+//! ```text
+//! move <size> <b|w> <cells>
+//! ```
+//!
+//! where `b` / `w` is its own colour and `<cells>` is the board row by row as in `Board::encode`
+//! (`.` empty, `b` black, `w` white) without line breaks, and answers with a move in standard notation, like `f5`.
+//! a bot is only asked when it has a legal move; passes are made for it.
+//! when the game is over, both get `end <result>`, with the result as in GGF (black's disc lead, like `+6`),
+//! and are stopped.
+//!
+//! a bot that doesn't answer within the time limit, answers with an illegal move or exits loses the game,
+//! and the winner is given every disc on the board.
+//!
+//! only works natively, as it needs processes and threads.
+use crate::ai::othello as ai;
+use crate::ggf::{GgfGame, GgfMove};
+use crate::rules::othello::{Board, Cell, Piece, Point};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bot {
+    /// a program, started with `sh -c <command>` so it may have arguments.
+    Command { name: String, command: String },
+    /// the built-in AI at `strength`, run in-process. handy to test bots against.
+    Ai { strength: usize },
+}
+impl Bot {
+    /// `ai:<strength>` for the built-in AI, and anything else as a command.
+    /// ```rust
+    /// use boardgame_ai::referee::Bot;
+    /// assert_eq!(Bot::parse("ai:3"), Bot::Ai { strength: 3 });
+    /// assert_eq!(Bot::parse("python3 bot.py").name(), "python3 bot.py");
+    /// ```
+    pub fn parse(spec: &str) -> Bot {
+        match spec.strip_prefix("ai:").and_then(|s| s.parse().ok()) {
+            Some(strength) => Bot::Ai {
+                strength: usize::clamp(strength, 1, 10),
+            },
+            None => Bot::Command {
+                name: spec.to_string(),
+                command: spec.to_string(),
+            },
+        }
+    }
+    pub fn name(&self) -> String {
+        match self {
+            Bot::Command { name, .. } => name.clone(),
+            Bot::Ai { strength } => format!("ai:{}", strength),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Forfeit {
+    CouldNotStart(String),
+    Timeout,
+    /// exited, or closed its stdin or stdout.
+    Crashed,
+    /// the answer, which wasn't a legal move.
+    IllegalMove(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameResult {
+    /// the moves played, up to a forfeit if any.
+    pub record: GgfGame,
+    /// who forfeited, and why.
+    pub forfeit: Option<(Piece, Forfeit)>,
+    pub black: usize,
+    pub white: usize,
+}
+impl GameResult {
+    /// None if it's a draw.
+    pub fn winner(&self) -> Option<Piece> {
+        match self.black.cmp(&self.white) {
+            std::cmp::Ordering::Greater => Some(Piece::Black),
+            std::cmp::Ordering::Less => Some(Piece::White),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+// a running bot
+enum Player {
+    Process {
+        child: Child,
+        stdin: ChildStdin,
+        lines: Receiver<String>,
+    },
+    Ai {
+        strength: usize,
+    },
+}
+impl Player {
+    fn start(bot: &Bot) -> Result<Player, Forfeit> {
+        let command = match bot {
+            Bot::Ai { strength } => {
+                return Ok(Player::Ai {
+                    strength: *strength,
+                })
+            }
+            Bot::Command { command, .. } => command,
+        };
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|error| Forfeit::CouldNotStart(error.to_string()))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        // a thread reads the lines, so waiting for one can time out
+        let (sender, lines) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    return;
+                };
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Player::Process {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    fn send(&mut self, message: &str) -> Result<(), Forfeit> {
+        match self {
+            Player::Process { stdin, .. } => writeln!(stdin, "{}", message)
+                .and_then(|_| stdin.flush())
+                .map_err(|_| Forfeit::Crashed),
+            Player::Ai { .. } => Ok(()),
+        }
+    }
+
+    fn play(&mut self, board: &Board, next: Piece, limit: Duration) -> Result<Point, Forfeit> {
+        let (stdin, lines) = match self {
+            Player::Ai { strength } => {
+                return ai::predict(board, next, *strength, *strength).ok_or(Forfeit::Crashed)
+            }
+            Player::Process { stdin, lines, .. } => (stdin, lines),
+        };
+        // anything sent before the question is stale
+        while lines.try_recv().is_ok() {}
+        let message = format!("move {} {} {}", board.width(), colour(next), cells(board));
+        writeln!(stdin, "{}", message)
+            .and_then(|_| stdin.flush())
+            .map_err(|_| Forfeit::Crashed)?;
+        let answer = match lines.recv_timeout(limit) {
+            Ok(answer) => answer,
+            Err(RecvTimeoutError::Timeout) => return Err(Forfeit::Timeout),
+            Err(RecvTimeoutError::Disconnected) => return Err(Forfeit::Crashed),
+        };
+        match Point::from_notation(&answer) {
+            Some(at) if board.count_flips(at, next) > 0 => Ok(at),
+            _ => Err(Forfeit::IllegalMove(answer)),
+        }
+    }
+}
+impl Drop for Player {
+    fn drop(&mut self) {
+        if let Player::Process { child, .. } = self {
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+fn colour(piece: Piece) -> char {
+    match piece {
+        Piece::Black => 'b',
+        Piece::White => 'w',
+    }
+}
+fn cells(board: &Board) -> String {
+    board.encode().lines().collect()
+}
+
+/// plays a game from `board` with black to move, giving each bot `limit` for every move.
+pub fn play(black: &Bot, white: &Bot, board: Board, limit: Duration) -> GameResult {
+    let mut record = GgfGame::new(board.clone(), Piece::Black);
+    record.black = black.name();
+    record.white = white.name();
+    let (mut board, mut next) = (board, Piece::Black);
+    let start = |bot, piece| Player::start(bot).map_err(|forfeit| (piece, forfeit));
+    let players = start(black, Piece::Black).and_then(|b| Ok((b, start(white, Piece::White)?)));
+    let forfeit = match players {
+        Err(forfeit) => Some(forfeit),
+        Ok((mut black, mut white)) => {
+            let mut forfeit = None;
+            while !board.is_over() {
+                let player = match next {
+                    Piece::Black => &mut black,
+                    Piece::White => &mut white,
+                };
+                let at = if board.placeable(next).is_empty() {
+                    None
+                } else {
+                    match player.play(&board, next, limit) {
+                        Ok(at) => Some(at),
+                        Err(reason) => {
+                            forfeit = Some((next, reason));
+                            break;
+                        }
+                    }
+                };
+                if let Some(at) = at {
+                    board = board.place(at, next).expect("checked with count_flips");
+                }
+                record.moves.push(GgfMove { player: next, at });
+                next = next.flip();
+            }
+            let (black_score, white_score) = scores(&board, &forfeit);
+            let end = format!("end {}", result(black_score, white_score, &forfeit));
+            // they may well be gone already
+            black.send(&end).ok();
+            white.send(&end).ok();
+            forfeit
+        }
+    };
+    let (black_score, white_score) = scores(&board, &forfeit);
+    record.result = result(black_score, white_score, &forfeit);
+    GameResult {
+        record,
+        forfeit,
+        black: black_score,
+        white: white_score,
+    }
+}
+
+fn scores(board: &Board, forfeit: &Option<(Piece, Forfeit)>) -> (usize, usize) {
+    let cells = board.clone().cells();
+    let all = cells
+        .iter()
+        .filter(|(_, cell)| *cell != Cell::Blocked)
+        .count();
+    match forfeit {
+        Some((Piece::Black, _)) => (0, all),
+        Some((Piece::White, _)) => (all, 0),
+        None => (board.score(Piece::Black), board.score(Piece::White)),
+    }
+}
+
+// black's lead, with `:t` for a timeout and `:r` for any other forfeit, like GGS
+fn result(black: usize, white: usize, forfeit: &Option<(Piece, Forfeit)>) -> String {
+    let suffix = match forfeit {
+        None => "",
+        Some((_, Forfeit::Timeout)) => ":t",
+        Some(_) => ":r",
+    };
+    format!("{:+}{}", black as isize - white as isize, suffix)
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    const LIMIT: Duration = Duration::from_millis(2000);
+    fn command(command: &str) -> Bot {
+        Bot::Command {
+            name: command.to_string(),
+            command: command.to_string(),
+        }
+    }
+    #[test]
+    fn ai_against_ai() {
+        let result = play(
+            &Bot::parse("ai:1"),
+            &Bot::parse("ai:2"),
+            Board::new(4, 4),
+            LIMIT,
+        );
+        assert_eq!(result.forfeit, None);
+        let (board, _) = result.record.position().unwrap();
+        assert!(board.is_over());
+        assert_eq!(result.black, board.score(Piece::Black));
+        assert!(result.record.result.starts_with(['+', '-']));
+    }
+    #[test]
+    fn bot_gets_the_position() {
+        // answers with the first legal move on the starting 4x4 board, then crashes
+        let bot =
+            command(r#"read line; [ "$line" = "move 4 b .....bw..wb....." ] && echo c1; sleep 5"#);
+        let result = play(&bot, &Bot::parse("ai:1"), Board::new(4, 4), LIMIT);
+        let first = result.record.moves[0];
+        assert_eq!(first.at, Some(Point::new(2, 0)));
+        assert_eq!(result.forfeit, Some((Piece::Black, Forfeit::Timeout)));
+        assert_eq!((result.black, result.white), (0, 16));
+        assert_eq!(result.record.result, "-16:t");
+    }
+    #[test]
+    fn forfeits() {
+        let board = Board::new(4, 4);
+        let illegal = play(
+            &command("read line; echo a1"),
+            &Bot::parse("ai:1"),
+            board.clone(),
+            LIMIT,
+        );
+        assert_eq!(
+            illegal.forfeit,
+            Some((Piece::Black, Forfeit::IllegalMove("a1".to_string())))
+        );
+        let crashed = play(&Bot::parse("ai:1"), &command("exit 1"), board, LIMIT);
+        assert_eq!(crashed.forfeit, Some((Piece::White, Forfeit::Crashed)));
+        assert_eq!(crashed.winner(), Some(Piece::Black));
+        assert_eq!(crashed.record.result, "+16:r");
+    }
+}
@@ -0,0 +1,453 @@
+//! ataxx: move a piece one cell away to clone it, or two cells away to jump there.
+//! either way, every opponent piece next to where it lands turns into yours.
+//!
+//! distances are counted like a king moves in chess, so one cell away includes diagonals.
+//! a player with no moves must pass. the game ends when the board is full, when a player has no pieces,
+//! or when neither player can move. whoever has more pieces wins.
+use super::othello::{Cell, Piece, Point};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct AtaxxMove {
+    pub from: Point,
+    pub to: Point,
+}
+impl AtaxxMove {
+    pub fn new(from: Point, to: Point) -> Self {
+        AtaxxMove { from, to }
+    }
+    /// how far the piece moves. 1 is a clone, 2 is a jump.
+    pub fn distance(&self) -> usize {
+        self.from
+            .x
+            .abs_diff(self.to.x)
+            .max(self.from.y.abs_diff(self.to.y))
+    }
+    pub fn is_clone(&self) -> bool {
+        self.distance() == 1
+    }
+}
+#[wasm_bindgen]
+impl AtaxxMove {
+    pub fn create(from: &Point, to: &Point) -> AtaxxMove {
+        AtaxxMove::new(*from, *to)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct AtaxxBoard {
+    pub width: usize,
+    pub height: usize,
+    data: Vec<Vec<Cell>>,
+}
+
+#[wasm_bindgen]
+impl AtaxxBoard {
+    /// should return Array<Array<"." | "b" | "w" | "#">>, like `Board::get_data`.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = self
+            .data
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Empty => r#"".""#,
+                        Cell::Black => r#""b""#,
+                        Cell::White => r#""w""#,
+                        Cell::Blocked => r##""#""##,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    pub fn score(&self, player: Piece) -> usize {
+        let cell: Cell = player.into();
+        self.data.iter().flatten().filter(|&&c| c == cell).count()
+    }
+
+    /// true iff `player` has no moves and has to pass.
+    pub fn must_pass(&self, player: Piece) -> bool {
+        self.moves(player).is_empty()
+    }
+
+    /// the board is full, a player has no pieces left, or neither player can move.
+    pub fn is_over(&self) -> bool {
+        let full = !self.data.iter().flatten().any(|&c| c == Cell::Empty);
+        full || self.score(Piece::Black) == 0
+            || self.score(Piece::White) == 0
+            || (self.must_pass(Piece::Black) && self.must_pass(Piece::White))
+    }
+
+    /// who has more pieces. None if it's a tie.
+    pub fn winner(&self) -> Option<Piece> {
+        let black = self.score(Piece::Black);
+        let white = self.score(Piece::White);
+        match black.cmp(&white) {
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(Piece::Black),
+            std::cmp::Ordering::Less => Some(Piece::White),
+        }
+    }
+}
+
+impl AtaxxBoard {
+    /// creates a board with each player's two pieces in opposite corners. black moves first.
+    /// ```rust
+    /// use boardgame_ai::rules::ataxx::AtaxxBoard;
+    /// let expected = "
+    /// b...w
+    /// .....
+    /// .....
+    /// .....
+    /// w...b
+    /// ";
+    /// assert_eq!(AtaxxBoard::new(5, 5).encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::try_new(width, height).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// the starting board. width and height must be between 2 and 254.
+    pub fn try_new(width: usize, height: usize) -> Result<Self, GameError> {
+        if !(2..255).contains(&width) || !(2..255).contains(&height) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "width and height should be between 2 and 254",
+            ));
+        }
+        let mut data = vec![vec![Cell::Empty; width]; height];
+        data[0][0] = Cell::Black;
+        data[height - 1][width - 1] = Cell::Black;
+        data[0][width - 1] = Cell::White;
+        data[height - 1][0] = Cell::White;
+        Ok(AtaxxBoard {
+            width,
+            height,
+            data,
+        })
+    }
+    pub fn get(&self, at: Point) -> Result<Cell, String> {
+        Ok(self
+            .data
+            .get(at.y)
+            .ok_or(super::othello::OutOfBoundaryError())?
+            .get(at.x)
+            .ok_or(super::othello::OutOfBoundaryError())?
+            .to_owned())
+    }
+    /// puts `cell` at `at` regardless of the rules, e.g. to block cells before the game starts.
+    pub fn set(&mut self, at: Point, cell: Cell) -> Result<(), String> {
+        *self
+            .data
+            .get_mut(at.y)
+            .ok_or(super::othello::OutOfBoundaryError())?
+            .get_mut(at.x)
+            .ok_or(super::othello::OutOfBoundaryError())? = cell;
+        Ok(())
+    }
+    /// every cell, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Point::new(x, y)))
+    }
+    // cells up to `distance` away from `at`, excluding `at` itself.
+    fn around(&self, at: Point, distance: usize) -> impl Iterator<Item = Point> + '_ {
+        let distance = distance as isize;
+        (-distance..=distance)
+            .flat_map(move |dy| (-distance..=distance).map(move |dx| (dx, dy)))
+            .filter(|&d| d != (0, 0))
+            .filter_map(move |(dx, dy)| {
+                let x = at.x.checked_add_signed(dx)?;
+                let y = at.y.checked_add_signed(dy)?;
+                (x < self.width && y < self.height).then(|| Point::new(x, y))
+            })
+    }
+    /// count of opponent pieces that `player` would take by landing on `at`.
+    pub fn count_captures(&self, at: Point, player: Piece) -> usize {
+        let opponent: Cell = player.flip().into();
+        self.around(at, 1)
+            .filter(|&p| self.get(p) == Ok(opponent))
+            .count()
+    }
+    /// every move `player` can make. cloning onto a cell is the same move whichever piece it comes from,
+    /// so there's only one clone per cell. clones come first, then jumps.
+    pub fn moves(&self, player: Piece) -> Vec<AtaxxMove> {
+        let own: Cell = player.into();
+        let pieces: Vec<_> = self.points().filter(|&p| self.get(p) == Ok(own)).collect();
+        let mut clones = Vec::new();
+        let mut jumps = Vec::new();
+        for to in self.points().filter(|&p| self.get(p) == Ok(Cell::Empty)) {
+            let near = pieces.iter().map(|&from| AtaxxMove::new(from, to));
+            if let Some(clone) = near.clone().find(|m| m.is_clone()) {
+                clones.push(clone);
+            }
+            jumps.extend(near.filter(|m| m.distance() == 2));
+        }
+        clones.extend(jumps);
+        clones
+    }
+    /// moves a piece of `player`, and takes every opponent piece around where it lands.
+    pub fn play(mut self, play: AtaxxMove, player: Piece) -> Result<AtaxxBoard, PlaceError> {
+        let AtaxxMove { from, to } = play;
+        let (Ok(from_cell), Ok(to_cell)) = (self.get(from), self.get(to)) else {
+            return Err(PlaceError::OutOfBoundary);
+        };
+        if from_cell != player.into() {
+            return Err(PlaceError::NotYourPiece);
+        }
+        match to_cell {
+            Cell::Empty => {}
+            Cell::Blocked => return Err(PlaceError::Blocked),
+            _ => return Err(PlaceError::AlreadyOccupied),
+        }
+        match play.distance() {
+            1 => {}
+            2 => self.data[from.y][from.x] = Cell::Empty,
+            _ => return Err(PlaceError::TooFar),
+        }
+        self.data[to.y][to.x] = player.into();
+        let opponent: Cell = player.flip().into();
+        let captured: Vec<_> = self
+            .around(to, 1)
+            .filter(|&p| self.get(p) == Ok(opponent))
+            .collect();
+        for p in captured {
+            self.data[p.y][p.x] = player.into();
+        }
+        Ok(self)
+    }
+
+    /// same format as `othello::Board::decode`, including blocked cells.
+    pub fn decode(
+        serialized: &str,
+        width: usize,
+        height: usize,
+    ) -> Result<AtaxxBoard, DecodeError> {
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != height {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: height,
+                got: rows.len(),
+            });
+        }
+        let mut board = AtaxxBoard::new(width, height);
+        for (at, line) in rows.into_iter().enumerate() {
+            let chars: Vec<_> = line.chars().collect();
+            if chars.len() != width {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at,
+                    expected: width,
+                    got: chars.len(),
+                });
+            }
+            for (x, char) in chars.into_iter().enumerate() {
+                board.data[at][x] = match char {
+                    '.' | '_' => Cell::Empty,
+                    'b' => Cell::Black,
+                    'w' => Cell::White,
+                    '#' => Cell::Blocked,
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                };
+            }
+        }
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let rows = self.data.iter().map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Cell::Empty => '.',
+                    Cell::Black => 'b',
+                    Cell::White => 'w',
+                    Cell::Blocked => '#',
+                })
+                .collect::<String>()
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for AtaxxBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    NotYourPiece,
+    AlreadyOccupied,
+    Blocked,
+    /// the destination is more than two cells away.
+    TooFar,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to move piece. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::NotYourPiece => GameErrorKind::NotYourPiece,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::Blocked => GameErrorKind::Blocked,
+            PlaceError::TooFar => GameErrorKind::TooFar,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn clone_and_jump() {
+        let input = "
+            b....
+            .....
+            ..w..
+            ...w.
+            .....
+        ";
+        let board = AtaxxBoard::decode(input, 5, 5).unwrap();
+        let cloned = board
+            .clone()
+            .play(
+                AtaxxMove::new(Point::new(0, 0), Point::new(1, 1)),
+                Piece::Black,
+            )
+            .unwrap();
+        let expected = "
+            b....
+            .b...
+            ..b..
+            ...w.
+            .....
+        ";
+        assert_eq!(cloned, AtaxxBoard::decode(expected, 5, 5).unwrap());
+        let jumped = board
+            .play(
+                AtaxxMove::new(Point::new(0, 0), Point::new(2, 1)),
+                Piece::Black,
+            )
+            .unwrap();
+        let expected = "
+            .....
+            ..b..
+            ..b..
+            ...w.
+            .....
+        ";
+        assert_eq!(jumped, AtaxxBoard::decode(expected, 5, 5).unwrap());
+    }
+    #[test]
+    fn moves() {
+        // 3 clones and 5 jumps from each corner
+        let board = AtaxxBoard::new(7, 7);
+        let moves = board.moves(Piece::Black);
+        assert_eq!(moves.len(), 16);
+        assert_eq!(moves.iter().filter(|m| m.is_clone()).count(), 6);
+        // both pieces can clone onto (1, 1), but it's one move
+        let input = "
+            b.b
+            ...
+            ##w
+        ";
+        let board = AtaxxBoard::decode(input, 3, 3).unwrap();
+        let clones: Vec<_> = board
+            .moves(Piece::Black)
+            .into_iter()
+            .filter(|m| m.is_clone())
+            .map(|m| m.to)
+            .collect();
+        assert_eq!(
+            clones,
+            vec![
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(2, 1)
+            ]
+        );
+    }
+    #[test]
+    fn place_errors() {
+        let input = "
+            b.#
+            ...
+            ..w
+        ";
+        let board = AtaxxBoard::decode(input, 3, 3).unwrap();
+        let play = |from: (usize, usize), to: (usize, usize), player| {
+            let from = Point::new(from.0, from.1);
+            let to = Point::new(to.0, to.1);
+            board.clone().play(AtaxxMove::new(from, to), player)
+        };
+        assert_eq!(play((0, 0), (2, 0), Piece::Black), Err(PlaceError::Blocked));
+        assert_eq!(
+            play((0, 0), (2, 2), Piece::Black),
+            Err(PlaceError::AlreadyOccupied)
+        );
+        assert_eq!(
+            play((2, 2), (1, 1), Piece::Black),
+            Err(PlaceError::NotYourPiece)
+        );
+        assert_eq!(
+            play((0, 0), (3, 0), Piece::Black),
+            Err(PlaceError::OutOfBoundary)
+        );
+        let input = "
+            b......
+            .......
+            ......w
+        ";
+        let board = AtaxxBoard::decode(input, 7, 3).unwrap();
+        let far = AtaxxMove::new(Point::new(0, 0), Point::new(3, 0));
+        assert_eq!(board.play(far, Piece::Black), Err(PlaceError::TooFar));
+    }
+    #[test]
+    fn game_end() {
+        let wiped_out = "
+            bb.
+            ...
+            ...
+        ";
+        let board = AtaxxBoard::decode(wiped_out, 3, 3).unwrap();
+        assert!(board.is_over());
+        assert_eq!(board.winner(), Some(Piece::Black));
+        // white is walled in and has to pass, but black can still move
+        let walled = "
+            w##...
+            ###...
+            ###...
+            .....b
+        ";
+        let board = AtaxxBoard::decode(walled, 6, 4).unwrap();
+        assert!(board.must_pass(Piece::White));
+        assert!(!board.is_over());
+        let full = "
+            bw
+            wb
+        ";
+        let board = AtaxxBoard::decode(full, 2, 2).unwrap();
+        assert!(board.is_over());
+        assert_eq!(board.winner(), None);
+    }
+}
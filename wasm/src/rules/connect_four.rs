@@ -0,0 +1,397 @@
+//! connect four, with any board size up to 128 cells (plus a spare row) and any line length.
+//!
+//! the board is a pair of bitboards. bit `column * (height + 1) + row` is the cell at `column`,
+//! `row` rows from the bottom. every column has one spare bit on top, which is always 0,
+//! so shifting a line past the top of a column can't wrap into the next one.
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[wasm_bindgen]
+pub enum Disc {
+    /// moves first.
+    Red,
+    Yellow,
+}
+impl Disc {
+    pub fn flip(&self) -> Self {
+        match self {
+            Disc::Red => Disc::Yellow,
+            Disc::Yellow => Disc::Red,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[wasm_bindgen]
+pub struct ConnectFourBoard {
+    pub width: usize,
+    pub height: usize,
+    /// how many in a row wins.
+    pub connect: usize,
+    red: u128,
+    yellow: u128,
+}
+
+#[wasm_bindgen]
+impl ConnectFourBoard {
+    /// should return Array<Array<"." | "r" | "y">>, top row first.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = (0..self.height)
+            .rev()
+            .map(|row| {
+                let cells = (0..self.width)
+                    .map(|column| match self.get(column, row) {
+                        Some(Disc::Red) => r#""r""#,
+                        Some(Disc::Yellow) => r#""y""#,
+                        None => r#"".""#,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    /// the player who drops next. red goes first.
+    pub fn turn(&self) -> Disc {
+        if self.red.count_ones() == self.yellow.count_ones() {
+            Disc::Red
+        } else {
+            Disc::Yellow
+        }
+    }
+
+    /// the player who has `connect` in a row, if any.
+    pub fn winner(&self) -> Option<Disc> {
+        if self.has_line(self.red) {
+            Some(Disc::Red)
+        } else if self.has_line(self.yellow) {
+            Some(Disc::Yellow)
+        } else {
+            None
+        }
+    }
+
+    /// someone has won, or the board is full.
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some() || self.moves_played() == self.width * self.height
+    }
+
+    pub fn can_place(&self, column: usize) -> bool {
+        column < self.width && self.mask() & self.top_mask(column) == 0
+    }
+}
+
+impl ConnectFourBoard {
+    /// ```rust
+    /// use boardgame_ai::rules::connect_four::*;
+    /// let board = ConnectFourBoard::new(7, 6, 4);
+    /// let board = board.place(3, Disc::Red).unwrap();
+    /// let board = board.place(3, Disc::Yellow).unwrap();
+    /// let expected = "
+    /// .......
+    /// .......
+    /// .......
+    /// .......
+    /// ...y...
+    /// ...r...
+    /// ";
+    /// assert_eq!(board.encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(width: usize, height: usize, connect: usize) -> Self {
+        Self::try_new(width, height, connect).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// an empty board. it must fit in the bitboards, so (height + 1) * width can be 128 at most.
+    pub fn try_new(width: usize, height: usize, connect: usize) -> Result<Self, GameError> {
+        let message = if width == 0 || height == 0 {
+            "board must not be empty"
+        } else if (height + 1) * width > 128 {
+            "(height + 1) * width should not be larger than 128"
+        } else if connect < 2 {
+            "connect must be at least 2"
+        } else {
+            return Ok(ConnectFourBoard {
+                width,
+                height,
+                connect,
+                red: 0,
+                yellow: 0,
+            });
+        };
+        Err(GameError::new(GameErrorKind::InvalidSize, message))
+    }
+    fn bit(&self, column: usize, row: usize) -> u128 {
+        1 << (column * (self.height + 1) + row)
+    }
+    fn top_mask(&self, column: usize) -> u128 {
+        self.bit(column, self.height - 1)
+    }
+    /// every occupied cell.
+    pub fn mask(&self) -> u128 {
+        self.red | self.yellow
+    }
+    /// every cell of `disc`.
+    pub fn discs(&self, disc: Disc) -> u128 {
+        match disc {
+            Disc::Red => self.red,
+            Disc::Yellow => self.yellow,
+        }
+    }
+    pub fn moves_played(&self) -> usize {
+        self.mask().count_ones() as usize
+    }
+    /// the cell `row` rows from the bottom of `column`. None if it's empty or out of the board.
+    pub fn get(&self, column: usize, row: usize) -> Option<Disc> {
+        if column >= self.width || row >= self.height {
+            return None;
+        }
+        let bit = self.bit(column, row);
+        if self.red & bit != 0 {
+            Some(Disc::Red)
+        } else if self.yellow & bit != 0 {
+            Some(Disc::Yellow)
+        } else {
+            None
+        }
+    }
+    /// the row a disc dropped into `column` would land on.
+    pub fn landing_row(&self, column: usize) -> Option<usize> {
+        if !self.can_place(column) {
+            return None;
+        }
+        (0..self.height).find(|&row| self.mask() & self.bit(column, row) == 0)
+    }
+    /// the bit a disc dropped into `column` would land on. 0 if the column is full.
+    pub fn landing_bit(&self, column: usize) -> u128 {
+        self.landing_row(column)
+            .map_or(0, |row| self.bit(column, row))
+    }
+    /// true iff `discs` contains `connect` in a row.
+    pub fn has_line(&self, discs: u128) -> bool {
+        let h = self.height;
+        // vertical, horizontal, and the two diagonals
+        [1, h + 1, h, h + 2].iter().any(|&shift| {
+            let mut line = discs;
+            for _ in 1..self.connect {
+                line &= line >> shift;
+            }
+            line != 0
+        })
+    }
+    /// every column that can still take a disc, from the left.
+    pub fn placeable(&self) -> Vec<usize> {
+        (0..self.width)
+            .filter(|&column| self.can_place(column))
+            .collect()
+    }
+    /// drops `disc` into `column`.
+    pub fn place(mut self, column: usize, disc: Disc) -> Result<ConnectFourBoard, PlaceError> {
+        if self.winner().is_some() {
+            return Err(PlaceError::GameOver);
+        }
+        if disc != self.turn() {
+            return Err(PlaceError::NotYourTurn);
+        }
+        if column >= self.width {
+            return Err(PlaceError::OutOfBoundary);
+        }
+        let bit = self.landing_bit(column);
+        if bit == 0 {
+            return Err(PlaceError::ColumnFull);
+        }
+        match disc {
+            Disc::Red => self.red |= bit,
+            Disc::Yellow => self.yellow |= bit,
+        }
+        Ok(self)
+    }
+
+    /// rows from the top, like `othello::Board::decode`. `r` is red, `y` is yellow.
+    /// discs don't have to be stacked, so this can describe positions that can't happen in a game.
+    pub fn decode(
+        serialized: &str,
+        width: usize,
+        height: usize,
+        connect: usize,
+    ) -> Result<ConnectFourBoard, DecodeError> {
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != height {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: height,
+                got: rows.len(),
+            });
+        }
+        let mut board = ConnectFourBoard::new(width, height, connect);
+        for (at, line) in rows.into_iter().enumerate() {
+            let row = height - 1 - at;
+            let chars: Vec<_> = line.chars().collect();
+            if chars.len() != width {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at,
+                    expected: width,
+                    got: chars.len(),
+                });
+            }
+            for (column, char) in chars.into_iter().enumerate() {
+                match char {
+                    '.' | '_' => {}
+                    'r' => board.red |= board.bit(column, row),
+                    'y' => board.yellow |= board.bit(column, row),
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                }
+            }
+        }
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let rows = (0..self.height).rev().map(|row| {
+            (0..self.width)
+                .map(|column| match self.get(column, row) {
+                    Some(Disc::Red) => 'r',
+                    Some(Disc::Yellow) => 'y',
+                    None => '.',
+                })
+                .collect::<String>()
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for ConnectFourBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    ColumnFull,
+    NotYourTurn,
+    GameOver,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to drop disc. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::ColumnFull => GameErrorKind::ColumnFull,
+            PlaceError::NotYourTurn => GameErrorKind::NotYourTurn,
+            PlaceError::GameOver => GameErrorKind::GameOver,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn lines() {
+        let vertical = "
+            .......
+            .......
+            ...r...
+            ...r...
+            ...ry..
+            ...ryy.
+        ";
+        let horizontal = "
+            .......
+            .......
+            .......
+            .......
+            ..rr...
+            yyyy.r.
+        ";
+        let diagonal = "
+            .......
+            .......
+            ...r...
+            ..ry...
+            .ryy...
+            ryyr...
+        ";
+        let anti_diagonal = "
+            .......
+            .......
+            r......
+            yr.....
+            yyr....
+            rryr...
+        ";
+        for (board, winner) in [
+            (vertical, Disc::Red),
+            (horizontal, Disc::Yellow),
+            (diagonal, Disc::Red),
+            (anti_diagonal, Disc::Red),
+        ] {
+            let board = ConnectFourBoard::decode(board, 7, 6, 4).unwrap();
+            assert_eq!(board.winner(), Some(winner), "{board:?}");
+            assert!(board.is_over());
+        }
+    }
+    #[test]
+    fn no_wrap_around() {
+        // the top of a column and the bottom of the next one are not connected
+        let board = "
+            r......
+            r......
+            r......
+            .......
+            .......
+            .r.....
+        ";
+        let board = ConnectFourBoard::decode(board, 7, 6, 4).unwrap();
+        assert_eq!(board.winner(), None);
+    }
+    #[test]
+    fn connect_n() {
+        let board = "
+            ......
+            ......
+            rrr...
+            yyy...
+        ";
+        let three = ConnectFourBoard::decode(board, 6, 4, 3).unwrap();
+        assert_eq!(three.winner(), Some(Disc::Red));
+        let five = ConnectFourBoard::decode(board, 6, 4, 5).unwrap();
+        assert_eq!(five.winner(), None);
+    }
+    #[test]
+    fn place_errors() {
+        let board = ConnectFourBoard::new(2, 2, 2);
+        assert_eq!(
+            board.clone().place(0, Disc::Yellow),
+            Err(PlaceError::NotYourTurn)
+        );
+        assert_eq!(
+            board.clone().place(2, Disc::Red),
+            Err(PlaceError::OutOfBoundary)
+        );
+        let board = board.place(0, Disc::Red).unwrap();
+        let board = board.place(0, Disc::Yellow).unwrap();
+        assert_eq!(board.placeable(), vec![1]);
+        assert_eq!(
+            board.clone().place(0, Disc::Red),
+            Err(PlaceError::ColumnFull)
+        );
+        let board = board.place(1, Disc::Red).unwrap();
+        assert_eq!(board.winner(), Some(Disc::Red));
+        assert_eq!(board.place(1, Disc::Yellow), Err(PlaceError::GameOver));
+    }
+}
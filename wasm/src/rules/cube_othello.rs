@@ -0,0 +1,435 @@
+//! othello in an N x N x N cube.
+//!
+//! lines go in 26 directions (every combination of -1, 0, 1 on the three axes but not staying still),
+//! otherwise the rules are the same as othello.
+use super::othello::{Cell, DecodeError, OutOfBoundaryError, Piece};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// the 3D version of `EIGHT_DIRECTIONS`.
+pub static TWENTY_SIX_DIRECTIONS: [Direction3; 26] = {
+    let mut dirs = [Direction3 { x: 0, y: 0, z: 0 }; 26];
+    let mut i = 0;
+    let mut n = 0;
+    while n < 27 {
+        let (x, y, z) = (
+            (n % 3) as isize - 1,
+            ((n / 3) % 3) as isize - 1,
+            (n / 9) as isize - 1,
+        );
+        if x != 0 || y != 0 || z != 0 {
+            dirs[i] = Direction3 { x, y, z };
+            i += 1;
+        }
+        n += 1;
+    }
+    dirs
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[wasm_bindgen]
+pub struct Point3 {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+impl Point3 {
+    pub fn new(x: usize, y: usize, z: usize) -> Self {
+        Point3 { x, y, z }
+    }
+    pub fn move_for(&self, d: Direction3) -> Result<Self, OutOfBoundaryError> {
+        Ok(Point3 {
+            x: self.x.checked_add_signed(d.x).ok_or(OutOfBoundaryError())?,
+            y: self.y.checked_add_signed(d.y).ok_or(OutOfBoundaryError())?,
+            z: self.z.checked_add_signed(d.z).ok_or(OutOfBoundaryError())?,
+        })
+    }
+}
+#[wasm_bindgen]
+impl Point3 {
+    pub fn create(x: usize, y: usize, z: usize) -> Point3 {
+        Point3::new(x, y, z)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction3 {
+    x: isize,
+    y: isize,
+    z: isize,
+}
+impl Direction3 {
+    pub fn times(&self, times: isize) -> Self {
+        Self {
+            x: self.x * times,
+            y: self.y * times,
+            z: self.z * times,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct CubeBoard {
+    // private, as `data` is laid out by it. JS reads it through `size()`.
+    size: usize,
+    // indexed by [z][y][x], flattened.
+    data: Vec<Cell>,
+}
+
+#[wasm_bindgen]
+impl CubeBoard {
+    /// the length of an edge of the cube.
+    #[wasm_bindgen(getter)]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /// should return Array<Array<Array<"." | "b" | "w">>>, indexed by [z][y][x].
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = self
+            .data
+            .chunks(self.size * self.size)
+            .map(|layer| {
+                let rows = layer
+                    .chunks(self.size)
+                    .map(|row| {
+                        let cells = row
+                            .iter()
+                            .map(|cell| match cell {
+                                Cell::Black => r#""b""#,
+                                Cell::White => r#""w""#,
+                                _ => r#"".""#,
+                            })
+                            .collect::<Vec<_>>()
+                            .join(",");
+                        format!("[{}]", cells)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", rows)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    pub fn score(&self, player: Piece) -> usize {
+        let player_cell: Cell = player.into();
+        self.data
+            .iter()
+            .filter(|&&cell| cell == player_cell)
+            .count()
+    }
+
+    /// true iff neither player can place anymore.
+    pub fn is_over(&self) -> bool {
+        self.placeable(Piece::Black).is_empty() && self.placeable(Piece::White).is_empty()
+    }
+
+    /// who has more discs. None if it's a tie.
+    pub fn winner(&self) -> Option<Piece> {
+        let black = self.score(Piece::Black);
+        let white = self.score(Piece::White);
+        match black.cmp(&white) {
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(Piece::Black),
+            std::cmp::Ordering::Less => Some(Piece::White),
+        }
+    }
+}
+
+impl CubeBoard {
+    /// creates a board with the centre 2 x 2 x 2 cube filled like a checkerboard,
+    /// so every layer through the centre looks like the othello starting position.
+    /// ```rust
+    /// use boardgame_ai::rules::cube_othello::CubeBoard;
+    /// let expected = "
+    /// ....
+    /// ....
+    /// ....
+    /// ....
+    ///
+    /// ....
+    /// .bw.
+    /// .wb.
+    /// ....
+    ///
+    /// ....
+    /// .wb.
+    /// .bw.
+    /// ....
+    ///
+    /// ....
+    /// ....
+    /// ....
+    /// ....
+    /// ";
+    /// assert_eq!(CubeBoard::new(4).encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(size: usize) -> Self {
+        Self::try_new(size).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// the starting board. the size must be even, and between 4 and 62.
+    pub fn try_new(size: usize) -> Result<Self, GameError> {
+        if !size.is_multiple_of(2) || !(4..64).contains(&size) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "size must be even, and between 4 and 62",
+            ));
+        }
+        let mut new = Self {
+            size,
+            data: vec![Cell::Empty; size * size * size],
+        };
+        let c = size / 2 - 1;
+        for z in c..c + 2 {
+            for y in c..c + 2 {
+                for x in c..c + 2 {
+                    let cell = if (x + y + z - 3 * c).is_multiple_of(2) {
+                        Cell::Black
+                    } else {
+                        Cell::White
+                    };
+                    new.set(Point3::new(x, y, z), cell)
+                        .expect("this shouldn't happen");
+                }
+            }
+        }
+        Ok(new)
+    }
+    fn index(&self, at: Point3) -> Result<usize, OutOfBoundaryError> {
+        if at.x >= self.size || at.y >= self.size || at.z >= self.size {
+            return Err(OutOfBoundaryError());
+        }
+        Ok((at.z * self.size + at.y) * self.size + at.x)
+    }
+    pub fn get(&self, at: Point3) -> Result<Cell, OutOfBoundaryError> {
+        Ok(self.data[self.index(at)?])
+    }
+    /// primitive operation. doesn't do anything other than setting the piece.
+    pub fn set(&mut self, at: Point3, cell: Cell) -> Result<(), OutOfBoundaryError> {
+        let index = self.index(at)?;
+        self.data[index] = cell;
+        Ok(())
+    }
+    /// every cell of the cube, layer by layer, then row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point3> + '_ {
+        let size = self.size;
+        (0..size).flat_map(move |z| {
+            (0..size).flat_map(move |y| (0..size).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+
+    fn count_flips_in_direction(&self, at: Point3, piece: Piece, dir: Direction3) -> usize {
+        let mut flipping = 0;
+        loop {
+            let Ok(pos) = at.move_for(dir.times(flipping as isize + 1)) else {
+                return 0;
+            };
+            let Ok(cell) = self.get(pos) else {
+                return 0;
+            };
+            if cell == piece.into() && flipping > 0 {
+                return flipping;
+            }
+            if cell != piece.flip().into() {
+                return 0;
+            }
+            flipping += 1;
+        }
+    }
+    pub fn count_flips(&self, at: Point3, piece: Piece) -> usize {
+        if self.get(at) != Ok(Cell::Empty) {
+            return 0;
+        }
+        TWENTY_SIX_DIRECTIONS
+            .iter()
+            .map(|&dir| self.count_flips_in_direction(at, piece, dir))
+            .sum()
+    }
+    pub fn placeable(&self, next: Piece) -> Vec<Point3> {
+        self.points()
+            .filter(|&point| self.count_flips(point, next) > 0)
+            .collect()
+    }
+    pub fn place(mut self, at: Point3, piece: Piece) -> Result<CubeBoard, PlaceError> {
+        match self.get(at) {
+            Err(_) => return Err(PlaceError::OutOfBoundary),
+            Ok(Cell::Empty) => {}
+            Ok(_) => return Err(PlaceError::AlreadyOccupied),
+        }
+        let flips: Vec<_> = TWENTY_SIX_DIRECTIONS
+            .iter()
+            .map(|&dir| (dir, self.count_flips_in_direction(at, piece, dir)))
+            .collect();
+        if flips.iter().all(|(_, count)| *count == 0) {
+            return Err(PlaceError::NoPiecesChanged);
+        }
+        self.set(at, piece.into()).unwrap(); // checked above
+        for (dir, count) in flips {
+            for i in 1..=count {
+                let pos = at.move_for(dir.times(i as isize)).unwrap(); // counted before, so it's on the board
+                self.set(pos, piece.into()).unwrap();
+            }
+        }
+        Ok(self)
+    }
+
+    /// layers from z = 0, each written like an othello board, separated by blank lines.
+    /// blank lines are only for readability, so the layers can be written without them too.
+    pub fn decode(serialized: &str, size: usize) -> Result<CubeBoard, DecodeError> {
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != size * size {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: size * size,
+                got: rows.len(),
+            });
+        }
+        let mut data = Vec::with_capacity(size * size * size);
+        for (at, row) in rows.into_iter().enumerate() {
+            let row = row
+                .chars()
+                .map(|char| match char {
+                    '.' | '_' => Ok(Cell::Empty),
+                    'w' => Ok(Cell::White),
+                    'b' => Ok(Cell::Black),
+                    _ => Err(DecodeError::UnknownChar(char)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if row.len() != size {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at,
+                    expected: size,
+                    got: row.len(),
+                });
+            }
+            data.extend(row);
+        }
+        Ok(CubeBoard { size, data })
+    }
+    pub fn encode(&self) -> String {
+        let layers = self.data.chunks(self.size * self.size).map(|layer| {
+            layer
+                .chunks(self.size)
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| match cell {
+                            Cell::Black => 'b',
+                            Cell::White => 'w',
+                            _ => '.',
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        "\n".to_string() + &layers.collect::<Vec<_>>().join("\n\n") + "\n"
+    }
+}
+impl fmt::Debug for CubeBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    AlreadyOccupied,
+    NoPiecesChanged,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to place piece at board. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::NoPiecesChanged => GameErrorKind::NoPiecesChanged,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn directions() {
+        assert!(!TWENTY_SIX_DIRECTIONS.contains(&Direction3 { x: 0, y: 0, z: 0 }));
+        for dir in TWENTY_SIX_DIRECTIONS.iter() {
+            let opposite = dir.times(-1);
+            assert!(TWENTY_SIX_DIRECTIONS.contains(&opposite));
+        }
+    }
+    #[test]
+    fn place_across_layers() {
+        let input = "
+            b...
+            ....
+            b.b.
+            ....
+
+            ....
+            .w..
+            .ww.
+            ....
+
+            ....
+            ....
+            ....
+            .b..
+
+            ....
+            ....
+            ....
+            ...w
+        ";
+        let expected = "
+            b...
+            ....
+            b.b.
+            ....
+
+            ....
+            .b..
+            .bb.
+            ....
+
+            ....
+            ....
+            ..b.
+            .b..
+
+            ....
+            ....
+            ....
+            ...w
+        ";
+        let board = CubeBoard::decode(input, 4).unwrap();
+        let expected = CubeBoard::decode(expected, 4).unwrap();
+        let at = Point3::new(2, 2, 2);
+        // (-1, -1, -1), (-1, 0, -1) and (0, 0, -1) capture.
+        // (-1, 1, 0) has nothing in between, and (1, 1, 1) runs into the edge.
+        assert_eq!(board.count_flips(at, Piece::Black), 3);
+        let board = board.place(at, Piece::Black).unwrap();
+        assert_eq!(board, expected);
+    }
+    #[test]
+    fn starting_position_is_playable() {
+        let board = CubeBoard::new(4);
+        assert!(!board.placeable(Piece::Black).is_empty());
+        assert!(!board.placeable(Piece::White).is_empty());
+        assert_eq!(board.score(Piece::Black), 4);
+        assert_eq!(CubeBoard::decode(&board.encode(), 4), Ok(board));
+    }
+}
@@ -0,0 +1,764 @@
+//! draughts (checkers), in two variants:
+//!
+//! - english checkers on 8x8. black moves first. men move and capture forwards only,
+//!   kings move and capture one square in any direction. any capture may be chosen,
+//!   and a man that reaches the far row during a capture is crowned and stops there.
+//! - international draughts on 10x10. white moves first. men capture backwards too,
+//!   kings fly over any number of empty squares, and the capture that takes the most pieces is mandatory.
+//!   a man is only crowned if its move ends on the far row.
+//!
+//! in both, capturing is mandatory, a capture must be continued while possible,
+//! and a captured piece is only removed after the move, so it can't be jumped twice.
+//! black starts at the top (row 0) and moves down, white starts at the bottom and moves up.
+//! a player who can't move loses. the game is drawn on a threefold repetition,
+//! or after too many moves without a capture or a man moving (40 each in english, 25 each in international).
+//!
+//! squares are numbered in the usual way: only dark squares count, from 1 at the top left to 32 (or 50) at the bottom right.
+//! moves are written as the squares the piece stops on, joined by `-` for a move and `x` for a capture, like `11-15` or `15x22x29`.
+use super::othello::{Piece, Point};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+static DIAGONALS: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+/// a move can stop on at most this many squares, which is more than any real game needs.
+pub const MAX_STOPS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[wasm_bindgen]
+pub enum DraughtsVariant {
+    #[default]
+    English,
+    International,
+}
+impl DraughtsVariant {
+    pub fn size(&self) -> usize {
+        match self {
+            DraughtsVariant::English => 8,
+            DraughtsVariant::International => 10,
+        }
+    }
+    fn first_player(&self) -> Piece {
+        match self {
+            DraughtsVariant::English => Piece::Black,
+            DraughtsVariant::International => Piece::White,
+        }
+    }
+    fn flying_kings(&self) -> bool {
+        *self == DraughtsVariant::International
+    }
+    // plies without a capture or a man moving before the game is drawn
+    fn quiet_limit(&self) -> usize {
+        match self {
+            DraughtsVariant::English => 80,
+            DraughtsVariant::International => 50,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Square {
+    Empty,
+    Man(Piece),
+    King(Piece),
+}
+impl Square {
+    pub fn piece(&self) -> Option<Piece> {
+        match self {
+            Square::Empty => None,
+            Square::Man(piece) | Square::King(piece) => Some(*piece),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DraughtsMove {
+    stops: [u8; MAX_STOPS],
+    len: u8,
+    /// bit `n - 1` is set iff square `n` is captured.
+    pub captured: u64,
+}
+impl DraughtsMove {
+    fn new(stops: &[u8], captured: u64) -> Self {
+        let mut array = [0; MAX_STOPS];
+        array[..stops.len()].copy_from_slice(stops);
+        DraughtsMove {
+            stops: array,
+            len: stops.len() as u8,
+            captured,
+        }
+    }
+    /// the squares the piece stops on, starting from where it is.
+    pub fn stops(&self) -> &[u8] {
+        &self.stops[..self.len as usize]
+    }
+    pub fn from(&self) -> u8 {
+        self.stops[0]
+    }
+    pub fn to(&self) -> u8 {
+        self.stops[self.len as usize - 1]
+    }
+    pub fn capture_count(&self) -> usize {
+        self.captured.count_ones() as usize
+    }
+    pub fn is_capture(&self) -> bool {
+        self.captured != 0
+    }
+    /// the same move, whichever way the piece went.
+    fn same_as(&self, other: &DraughtsMove) -> bool {
+        (self.from(), self.to(), self.captured) == (other.from(), other.to(), other.captured)
+    }
+}
+impl fmt::Display for DraughtsMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = if self.is_capture() { "x" } else { "-" };
+        let stops: Vec<_> = self.stops().iter().map(|n| n.to_string()).collect();
+        write!(f, "{}", stops.join(separator))
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct DraughtsBoard {
+    pub variant: DraughtsVariant,
+    turn: Piece,
+    // dark squares only, indexed by square number - 1
+    data: Vec<Square>,
+    // plies since the last capture or man move
+    quiet_plies: usize,
+    // positions since the last capture or man move, as repetitions can't go further back
+    history: Vec<(Vec<Square>, Piece)>,
+}
+
+#[wasm_bindgen]
+impl DraughtsBoard {
+    /// should return Array<Array<"." | "b" | "w" | "B" | "W">>. upper case letters are kings.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = (0..self.size())
+            .map(|y| {
+                let cells = (0..self.size())
+                    .map(|x| match self.get(Point::new(x, y)) {
+                        Ok(Square::Man(Piece::Black)) => r#""b""#,
+                        Ok(Square::Man(Piece::White)) => r#""w""#,
+                        Ok(Square::King(Piece::Black)) => r#""B""#,
+                        Ok(Square::King(Piece::White)) => r#""W""#,
+                        _ => r#"".""#,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    /// should return Array<string>, every legal move in notation.
+    pub fn legal_moves(&self) -> wasm_bindgen::JsValue {
+        let moves = self
+            .moves()
+            .iter()
+            .map(|play| format!(r#""{}""#, play))
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", moves)).unwrap()
+    }
+
+    pub fn size(&self) -> usize {
+        self.variant.size()
+    }
+
+    /// the player who moves next.
+    pub fn turn(&self) -> Piece {
+        self.turn
+    }
+
+    /// the other player, if the player to move can't move.
+    pub fn winner(&self) -> Option<Piece> {
+        self.moves().is_empty().then(|| self.turn.flip())
+    }
+
+    /// threefold repetition, or too long without a capture or a man moving.
+    pub fn is_draw(&self) -> bool {
+        let current = (self.data.clone(), self.turn);
+        let repetitions = self.history.iter().filter(|&p| *p == current).count();
+        repetitions >= 3 || self.quiet_plies >= self.variant.quiet_limit()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_draw() || self.winner().is_some()
+    }
+}
+
+impl DraughtsBoard {
+    /// creates the starting position: three rows of men each for english, four for international.
+    /// ```rust
+    /// use boardgame_ai::rules::draughts::*;
+    /// let expected = "
+    /// .b.b.b.b
+    /// b.b.b.b.
+    /// .b.b.b.b
+    /// ........
+    /// ........
+    /// w.w.w.w.
+    /// .w.w.w.w
+    /// w.w.w.w.
+    /// ";
+    /// assert_eq!(DraughtsBoard::new(DraughtsVariant::English).encode(), expected);
+    /// ```
+    pub fn new(variant: DraughtsVariant) -> Self {
+        let size = variant.size();
+        let rows = (size - 2) / 2;
+        let mut board = DraughtsBoard::empty(variant, variant.first_player());
+        for n in 1..=board.data.len() {
+            let y = board.point_of(n as u8).expect("this shouldn't happen").y;
+            board.data[n - 1] = if y < rows {
+                Square::Man(Piece::Black)
+            } else if y >= size - rows {
+                Square::Man(Piece::White)
+            } else {
+                Square::Empty
+            };
+        }
+        board.history = vec![(board.data.clone(), board.turn)];
+        board
+    }
+    fn empty(variant: DraughtsVariant, turn: Piece) -> Self {
+        let size = variant.size();
+        DraughtsBoard {
+            variant,
+            turn,
+            data: vec![Square::Empty; size * size / 2],
+            quiet_plies: 0,
+            history: Vec::new(),
+        }
+    }
+    /// the number of the dark square at `at`. None for light squares and outside of the board.
+    pub fn number_at(&self, at: Point) -> Option<u8> {
+        let size = self.size();
+        (at.x < size && at.y < size && (at.x + at.y) % 2 == 1)
+            .then(|| (at.y * size / 2 + at.x / 2 + 1) as u8)
+    }
+    /// where square `number` is. None if there's no such square.
+    pub fn point_of(&self, number: u8) -> Option<Point> {
+        let half = self.size() / 2;
+        let index = (number as usize)
+            .checked_sub(1)
+            .filter(|&i| i < self.data.len())?;
+        let y = index / half;
+        let x = index % half * 2 + (y + 1) % 2;
+        Some(Point::new(x, y))
+    }
+    /// light squares are always empty.
+    pub fn get(&self, at: Point) -> Result<Square, String> {
+        if at.x >= self.size() || at.y >= self.size() {
+            return Err(super::othello::OutOfBoundaryError().into());
+        }
+        Ok(self
+            .number_at(at)
+            .map_or(Square::Empty, |n| self.data[n as usize - 1]))
+    }
+    fn step(&self, at: Point, (dx, dy): (isize, isize), times: usize) -> Option<Point> {
+        let times = times as isize;
+        let x = at.x.checked_add_signed(dx * times)?;
+        let y = at.y.checked_add_signed(dy * times)?;
+        (x < self.size() && y < self.size()).then(|| Point::new(x, y))
+    }
+    fn forward(piece: Piece) -> isize {
+        match piece {
+            Piece::Black => 1,
+            Piece::White => -1,
+        }
+    }
+    fn crowning_row(&self, piece: Piece) -> usize {
+        match piece {
+            Piece::Black => self.size() - 1,
+            Piece::White => 0,
+        }
+    }
+
+    /// every legal move for the player to move.
+    pub fn moves(&self) -> Vec<DraughtsMove> {
+        let mut captures = Vec::new();
+        for n in 1..=self.data.len() as u8 {
+            if self.data[n as usize - 1].piece() == Some(self.turn) {
+                let mut stops = vec![n];
+                self.capture_from(n, &mut stops, 0, &mut captures);
+            }
+        }
+        if !captures.is_empty() {
+            if self.variant == DraughtsVariant::International {
+                let most = captures
+                    .iter()
+                    .map(|c| c.capture_count())
+                    .max()
+                    .unwrap_or(0);
+                captures.retain(|c| c.capture_count() == most);
+            }
+            let mut unique: Vec<DraughtsMove> = Vec::new();
+            for capture in captures {
+                if !unique.iter().any(|u| u.same_as(&capture)) {
+                    unique.push(capture);
+                }
+            }
+            return unique;
+        }
+        let mut moves = Vec::new();
+        for n in 1..=self.data.len() as u8 {
+            let square = self.data[n as usize - 1];
+            if square.piece() != Some(self.turn) {
+                continue;
+            }
+            let from = self.point_of(n).expect("this shouldn't happen");
+            let king = matches!(square, Square::King(_));
+            for &dir in DIAGONALS.iter() {
+                if !king && dir.1 != Self::forward(self.turn) {
+                    continue;
+                }
+                let reach = if king && self.variant.flying_kings() {
+                    self.size()
+                } else {
+                    1
+                };
+                for distance in 1..=reach {
+                    match self.step(from, dir, distance) {
+                        Some(to) if self.get(to) == Ok(Square::Empty) => {
+                            let to = self.number_at(to).expect("diagonals stay on dark squares");
+                            moves.push(DraughtsMove::new(&[n, to], 0));
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+        moves
+    }
+    // extends the capture in `stops` (starting from `stops[0]`) in every possible way,
+    // and pushes every finished one into `out`.
+    fn capture_from(
+        &self,
+        at: u8,
+        stops: &mut Vec<u8>,
+        captured: u64,
+        out: &mut Vec<DraughtsMove>,
+    ) {
+        let origin = stops[0];
+        let square = self.data[origin as usize - 1];
+        let (piece, king) = match square {
+            Square::Man(piece) => (piece, false),
+            Square::King(piece) => (piece, true),
+            Square::Empty => return,
+        };
+        let flying = king && self.variant.flying_kings();
+        // the moving piece has left its square
+        let is_empty = |p: Point| {
+            let n = self.number_at(p);
+            n == Some(origin) || self.get(p) == Ok(Square::Empty)
+        };
+        let from = self.point_of(at).expect("this shouldn't happen");
+        let mut extended = false;
+        for &dir in DIAGONALS.iter() {
+            let backwards = dir.1 != Self::forward(piece);
+            if !king && backwards && self.variant == DraughtsVariant::English {
+                continue;
+            }
+            // the first piece along the diagonal
+            let mut distance = 1;
+            let victim = loop {
+                match self.step(from, dir, distance) {
+                    Some(p) if is_empty(p) && flying => distance += 1,
+                    Some(p) if self.get(p).ok().and_then(|s| s.piece()) == Some(piece.flip()) => {
+                        break Some((p, distance))
+                    }
+                    _ => break None,
+                }
+            };
+            let Some((victim, distance)) = victim else {
+                continue;
+            };
+            let victim_bit = 1u64 << (self.number_at(victim).expect("this shouldn't happen") - 1);
+            if captured & victim_bit != 0 {
+                continue; // already jumped, and it's still in the way
+            }
+            let mut landing = distance + 1;
+            while let Some(land) = self.step(from, dir, landing).filter(|&p| is_empty(p)) {
+                extended = true;
+                let land_number = self
+                    .number_at(land)
+                    .expect("diagonals stay on dark squares");
+                stops.push(land_number);
+                let crowned = !king && land.y == self.crowning_row(piece);
+                if stops.len() == MAX_STOPS || (crowned && self.variant == DraughtsVariant::English)
+                {
+                    out.push(DraughtsMove::new(stops, captured | victim_bit));
+                } else {
+                    self.capture_from(land_number, stops, captured | victim_bit, out);
+                }
+                stops.pop();
+                if !flying {
+                    break;
+                }
+                landing += 1;
+            }
+        }
+        if !extended && stops.len() > 1 {
+            out.push(DraughtsMove::new(stops, captured));
+        }
+    }
+
+    /// plays `play`, which must be one of `moves()`. the path it takes doesn't matter.
+    pub fn play(mut self, play: &DraughtsMove) -> Result<DraughtsBoard, PlaceError> {
+        if self.is_draw() {
+            return Err(PlaceError::GameOver);
+        }
+        let moves = self.moves();
+        if moves.is_empty() {
+            return Err(PlaceError::GameOver);
+        }
+        if !moves.iter().any(|m| m.same_as(play)) {
+            return Err(PlaceError::IllegalMove);
+        }
+        let (from, to) = (play.from() as usize - 1, play.to() as usize - 1);
+        let mut square = std::mem::replace(&mut self.data[from], Square::Empty);
+        let man_moved = matches!(square, Square::Man(_));
+        let at = self.point_of(play.to()).expect("this shouldn't happen");
+        if let Square::Man(piece) = square {
+            if at.y == self.crowning_row(piece) {
+                square = Square::King(piece);
+            }
+        }
+        self.data[to] = square;
+        for (index, cell) in self.data.iter_mut().enumerate() {
+            if play.captured & (1 << index) != 0 {
+                *cell = Square::Empty;
+            }
+        }
+        self.turn = self.turn.flip();
+        if play.is_capture() || man_moved {
+            self.quiet_plies = 0;
+            self.history.clear();
+        } else {
+            self.quiet_plies += 1;
+        }
+        self.history.push((self.data.clone(), self.turn));
+        Ok(self)
+    }
+    /// the legal move written as `notation`. intermediate stops may be left out, like `15x29` for `15x22x29`.
+    /// ```rust
+    /// use boardgame_ai::rules::draughts::*;
+    /// let board = DraughtsBoard::new(DraughtsVariant::English);
+    /// let play = board.parse_move("11-15").unwrap();
+    /// assert_eq!((play.from(), play.to()), (11, 15));
+    /// assert_eq!(board.parse_move("11-14"), Err(PlaceError::IllegalMove));
+    /// assert_eq!(board.parse_move("eleven"), Err(PlaceError::BadNotation));
+    /// ```
+    pub fn parse_move(&self, notation: &str) -> Result<DraughtsMove, PlaceError> {
+        let stops = notation
+            .trim()
+            .split(['-', 'x'])
+            .map(|n| n.trim().parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| PlaceError::BadNotation)?;
+        if stops.len() < 2 {
+            return Err(PlaceError::BadNotation);
+        }
+        let moves = self.moves();
+        let exact = moves.iter().find(|m| m.stops() == stops.as_slice());
+        let ends = || {
+            moves.iter().find(|m| {
+                (m.from(), m.to()) == (stops[0], stops[stops.len() - 1]) && stops.len() == 2
+            })
+        };
+        exact.or_else(ends).copied().ok_or(PlaceError::IllegalMove)
+    }
+
+    /// rows from the top. `b` and `w` are men, `B` and `W` are kings, and `.` is an empty or light square.
+    pub fn decode(
+        serialized: &str,
+        variant: DraughtsVariant,
+        turn: Piece,
+    ) -> Result<DraughtsBoard, DecodeError> {
+        let size = variant.size();
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != size {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: size,
+                got: rows.len(),
+            });
+        }
+        let mut board = DraughtsBoard::empty(variant, turn);
+        for (y, line) in rows.into_iter().enumerate() {
+            let chars: Vec<_> = line.chars().collect();
+            if chars.len() != size {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at: y,
+                    expected: size,
+                    got: chars.len(),
+                });
+            }
+            for (x, char) in chars.into_iter().enumerate() {
+                let square = match char {
+                    '.' | '_' => Square::Empty,
+                    'b' => Square::Man(Piece::Black),
+                    'w' => Square::Man(Piece::White),
+                    'B' => Square::King(Piece::Black),
+                    'W' => Square::King(Piece::White),
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                };
+                match board.number_at(Point::new(x, y)) {
+                    Some(n) => board.data[n as usize - 1] = square,
+                    None if square == Square::Empty => {}
+                    None => return Err(DecodeError::UnknownChar(char)), // a piece on a light square
+                }
+            }
+        }
+        board.history = vec![(board.data.clone(), board.turn)];
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let rows = (0..self.size()).map(|y| {
+            (0..self.size())
+                .map(|x| match self.get(Point::new(x, y)) {
+                    Ok(Square::Man(Piece::Black)) => 'b',
+                    Ok(Square::Man(Piece::White)) => 'w',
+                    Ok(Square::King(Piece::Black)) => 'B',
+                    Ok(Square::King(Piece::White)) => 'W',
+                    _ => '.',
+                })
+                .collect::<String>()
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for DraughtsBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    /// not one of the legal moves. note that captures are mandatory.
+    IllegalMove,
+    BadNotation,
+    GameOver,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to move piece. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::IllegalMove => GameErrorKind::IllegalMove,
+            PlaceError::BadNotation => GameErrorKind::BadNotation,
+            PlaceError::GameOver => GameErrorKind::GameOver,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn notations(board: &DraughtsBoard) -> Vec<String> {
+        board.moves().iter().map(|m| m.to_string()).collect()
+    }
+    #[test]
+    fn numbering() {
+        let english = DraughtsBoard::new(DraughtsVariant::English);
+        assert_eq!(english.point_of(1), Some(Point::new(1, 0)));
+        assert_eq!(english.point_of(5), Some(Point::new(0, 1)));
+        assert_eq!(english.point_of(32), Some(Point::new(6, 7)));
+        assert_eq!(english.point_of(33), None);
+        assert_eq!(english.number_at(Point::new(0, 0)), None);
+        let international = DraughtsBoard::new(DraughtsVariant::International);
+        assert_eq!(international.point_of(50), Some(Point::new(8, 9)));
+        for n in 1..=50 {
+            assert_eq!(
+                international.number_at(international.point_of(n).unwrap()),
+                Some(n)
+            );
+        }
+    }
+    #[test]
+    fn opening_moves() {
+        let english = DraughtsBoard::new(DraughtsVariant::English);
+        assert_eq!(english.turn(), Piece::Black);
+        assert_eq!(
+            notations(&english),
+            vec!["9-13", "9-14", "10-14", "10-15", "11-15", "11-16", "12-16"]
+        );
+        let played = english
+            .clone()
+            .play(&english.parse_move("11-15").unwrap())
+            .unwrap();
+        assert_eq!(played.turn(), Piece::White);
+        let international = DraughtsBoard::new(DraughtsVariant::International);
+        assert_eq!(international.turn(), Piece::White);
+        assert_eq!(international.moves().len(), 9);
+    }
+    #[test]
+    fn capture_is_mandatory() {
+        let input = "
+            ........
+            ........
+            ...b....
+            ..w.....
+            ........
+            ........
+            ........
+            w.......
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        assert_eq!(notations(&board), vec!["10x17"]);
+        let simple = board.parse_move("10-14");
+        assert_eq!(simple, Err(PlaceError::IllegalMove));
+    }
+    #[test]
+    fn multi_jump() {
+        let input = "
+            ........
+            b.......
+            .w......
+            ........
+            ...w....
+            ........
+            ........
+            ........
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        assert_eq!(notations(&board), vec!["5x14x23"]);
+        // stopping halfway isn't allowed
+        assert_eq!(board.parse_move("5x14"), Err(PlaceError::IllegalMove));
+        let played = board
+            .clone()
+            .play(&board.parse_move("5x23").unwrap())
+            .unwrap();
+        let expected = "
+            ........
+            ........
+            ........
+            ........
+            ........
+            ....b...
+            ........
+            ........
+        ";
+        assert_eq!(played.encode().replace(' ', ""), expected.replace(' ', ""));
+    }
+    #[test]
+    fn english_crowning_ends_the_move() {
+        let input = "
+            ........
+            ........
+            ........
+            ........
+            ........
+            ..b.....
+            ...w.w..
+            ........
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        assert_eq!(notations(&board), vec!["22x31"]);
+        let played = board.clone().play(&board.moves()[0]).unwrap();
+        assert_eq!(played.get(Point::new(4, 7)), Ok(Square::King(Piece::Black)));
+        assert_eq!(played.get(Point::new(5, 6)), Ok(Square::Man(Piece::White)));
+    }
+    #[test]
+    fn international_captures() {
+        // the flying king takes two, so the man's single capture isn't allowed
+        let input = "
+            ..........
+            ..........
+            .......b..
+            ..........
+            ..........
+            ..........
+            ...b...b..
+            ........w.
+            ..........
+            W.........
+        ";
+        let board =
+            DraughtsBoard::decode(input, DraughtsVariant::International, Piece::White).unwrap();
+        let moves = board.moves();
+        assert_eq!(moves.len(), 2);
+        assert!(moves
+            .iter()
+            .all(|m| m.from() == 46 && m.capture_count() == 2));
+        let mut ends: Vec<_> = moves.iter().map(|m| m.to()).collect();
+        ends.sort();
+        assert_eq!(ends, vec![5, 10]);
+        // men capture backwards, but only in international
+        let input = "
+            ..........
+            ..........
+            ..........
+            ..........
+            ...b......
+            ....w.....
+            .....b....
+            ..........
+            ..........
+            ..........
+        ";
+        let board =
+            DraughtsBoard::decode(input, DraughtsVariant::International, Piece::White).unwrap();
+        assert_eq!(board.moves().len(), 2);
+        assert!(board.moves().iter().all(|m| m.is_capture()));
+    }
+    #[test]
+    fn threefold_repetition() {
+        let input = "
+            .B......
+            ........
+            ........
+            ........
+            ........
+            ........
+            ........
+            ......W.
+        ";
+        let mut board =
+            DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        let cycle = ["1-5", "32-28", "5-1", "28-32"];
+        for (i, notation) in cycle.iter().chain(cycle.iter()).enumerate() {
+            assert!(!board.is_draw(), "drawn after {} plies", i);
+            board = board
+                .clone()
+                .play(&board.parse_move(notation).unwrap())
+                .unwrap();
+        }
+        assert!(board.is_draw());
+        assert!(board.is_over());
+        assert_eq!(board.winner(), None);
+        assert_eq!(
+            board.clone().play(&board.moves()[0]),
+            Err(PlaceError::GameOver)
+        );
+    }
+    #[test]
+    fn no_moves_loses() {
+        let input = "
+            ........
+            ........
+            ........
+            ........
+            ........
+            b.......
+            .w......
+            ..w.....
+        ";
+        let board = DraughtsBoard::decode(input, DraughtsVariant::English, Piece::Black).unwrap();
+        assert_eq!(board.winner(), Some(Piece::White));
+        assert!(board.is_over());
+    }
+}
@@ -0,0 +1,472 @@
+//! gomoku: get five stones in a row, horizontally, vertically or diagonally. black moves first.
+//!
+//! with `GomokuRule::Renju`, black (who has a big first-move advantage) may not make
+//! an overline (six or more), a double four or a double three, unless the move also makes five.
+//! white has no restrictions, and wins with an overline too.
+use super::othello::{Cell, Piece, Point};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// one direction for each kind of line. the opposite ones are walked by negative steps.
+pub static FOUR_DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[wasm_bindgen]
+pub enum GomokuRule {
+    /// five or more in a row wins, for both players.
+    #[default]
+    FreeStyle,
+    /// black must make exactly five, and can't play forbidden moves.
+    Renju,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct GomokuBoard {
+    pub size: usize,
+    pub rule: GomokuRule,
+    data: Vec<Vec<Cell>>,
+}
+
+#[wasm_bindgen]
+impl GomokuBoard {
+    /// should return Array<Array<"." | "b" | "w">>, like `Board::get_data`.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = self
+            .data
+            .iter()
+            .map(|row| {
+                let cells = row
+                    .iter()
+                    .map(|cell| match cell {
+                        Cell::Black => r#""b""#,
+                        Cell::White => r#""w""#,
+                        _ => r#"".""#,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    /// the player who places next. black goes first.
+    pub fn turn(&self) -> Piece {
+        if self.count(Piece::Black) == self.count(Piece::White) {
+            Piece::Black
+        } else {
+            Piece::White
+        }
+    }
+
+    /// the player who has five in a row, if any.
+    pub fn winner(&self) -> Option<Piece> {
+        [Piece::Black, Piece::White].iter().copied().find(|&piece| {
+            self.points().any(|at| {
+                self.get(at) == Ok(piece.into())
+                    && FOUR_DIRECTIONS.iter().any(|&dir| {
+                        // only count each line from its first stone
+                        let previous = self.step(at, dir, -1);
+                        previous.map(|p| self.get(p)) != Some(Ok(piece.into()))
+                            && self.is_five(self.run(at, dir, piece, &[]), piece)
+                    })
+            })
+        })
+    }
+
+    /// someone has won, or the player to move has nowhere to place.
+    pub fn is_over(&self) -> bool {
+        let turn = self.turn();
+        self.winner().is_some()
+            || !self.points().any(|at| {
+                self.get(at) == Ok(Cell::Empty) && (turn == Piece::White || !self.is_forbidden(&at))
+            })
+    }
+
+    /// true iff `at` is empty and black may not place there. always false outside of renju.
+    pub fn is_forbidden(&self, at: &Point) -> bool {
+        let at = *at;
+        if self.rule != GomokuRule::Renju || self.get(at) != Ok(Cell::Empty) {
+            return false;
+        }
+        let runs: Vec<_> = FOUR_DIRECTIONS
+            .iter()
+            .map(|&dir| self.run(at, dir, Piece::Black, &[]))
+            .collect();
+        if runs.contains(&5) {
+            return false; // five wins, whatever else it makes
+        }
+        if runs.iter().any(|&run| run > 5) {
+            return true;
+        }
+        let fours: Vec<_> = FOUR_DIRECTIONS
+            .iter()
+            .map(|&dir| self.fours_in(at, dir, &[]))
+            .collect();
+        if fours.iter().sum::<usize>() >= 2 {
+            return true;
+        }
+        let threes = FOUR_DIRECTIONS
+            .iter()
+            .zip(fours)
+            .filter(|&(&dir, fours)| fours == 0 && self.is_three(at, dir))
+            .count();
+        threes >= 2
+    }
+}
+
+impl GomokuBoard {
+    /// creates an empty `size` x `size` board. 15 is the standard size for renju, 19 for free-style on a go board.
+    /// ```rust
+    /// use boardgame_ai::rules::gomoku::*;
+    /// use boardgame_ai::rules::othello::{Piece, Point};
+    /// let board = GomokuBoard::new(5, GomokuRule::FreeStyle);
+    /// let board = board.place(Point::new(2, 2), Piece::Black).unwrap();
+    /// let board = board.place(Point::new(1, 1), Piece::White).unwrap();
+    /// let expected = "
+    /// .....
+    /// .w...
+    /// ..b..
+    /// .....
+    /// .....
+    /// ";
+    /// assert_eq!(board.encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(size: usize, rule: GomokuRule) -> Self {
+        Self::try_new(size, rule).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// an empty board. the size must be between 5, so somebody can win, and 254.
+    pub fn try_new(size: usize, rule: GomokuRule) -> Result<Self, GameError> {
+        if !(5..255).contains(&size) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "size must be between 5 and 254",
+            ));
+        }
+        Ok(GomokuBoard {
+            size,
+            rule,
+            data: vec![vec![Cell::Empty; size]; size],
+        })
+    }
+    pub fn get(&self, at: Point) -> Result<Cell, String> {
+        Ok(self
+            .data
+            .get(at.y)
+            .ok_or(super::othello::OutOfBoundaryError())?
+            .get(at.x)
+            .ok_or(super::othello::OutOfBoundaryError())?
+            .to_owned())
+    }
+    /// every cell, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.size).flat_map(move |y| (0..self.size).map(move |x| Point::new(x, y)))
+    }
+    pub fn count(&self, piece: Piece) -> usize {
+        let cell: Cell = piece.into();
+        self.data.iter().flatten().filter(|&&c| c == cell).count()
+    }
+    /// `at` moved `times` steps towards `dir`, if it's still on the board.
+    pub fn step(&self, at: Point, (dx, dy): (isize, isize), times: isize) -> Option<Point> {
+        let x = at.x.checked_add_signed(dx * times)?;
+        let y = at.y.checked_add_signed(dy * times)?;
+        (x < self.size && y < self.size).then(|| Point::new(x, y))
+    }
+    /// length of the line of `piece` through `at` along `dir`, as if `at` and `extra` were `piece` too.
+    pub fn run(&self, at: Point, dir: (isize, isize), piece: Piece, extra: &[Point]) -> usize {
+        let is_piece = |p: Point| extra.contains(&p) || self.get(p) == Ok(piece.into());
+        let count = |sign: isize| {
+            (1..)
+                .map_while(|i| self.step(at, dir, sign * i))
+                .take_while(|&p| is_piece(p))
+                .count()
+        };
+        1 + count(1) + count(-1)
+    }
+    /// true iff a line of `run` stones of `piece` wins.
+    pub fn is_five(&self, run: usize, piece: Piece) -> bool {
+        let overline_wins = self.rule == GomokuRule::FreeStyle || piece == Piece::White;
+        run == 5 || (run > 5 && overline_wins)
+    }
+    /// true iff placing `piece` at `at` wins right away.
+    pub fn makes_five(&self, at: Point, piece: Piece) -> bool {
+        self.get(at) == Ok(Cell::Empty)
+            && FOUR_DIRECTIONS
+                .iter()
+                .any(|&dir| self.is_five(self.run(at, dir, piece, &[]), piece))
+    }
+    /// every point where `piece` would win right away.
+    pub fn winning_points(&self, piece: Piece) -> Vec<Point> {
+        self.points()
+            .filter(|&at| self.makes_five(at, piece))
+            .collect()
+    }
+    // steps along `dir` from `at` where black would make exactly five through `at`,
+    // with black stones at `at` and `extra`.
+    fn five_steps(&self, at: Point, dir: (isize, isize), extra: &[Point]) -> Vec<isize> {
+        (-4..=4)
+            .filter(|&k| k != 0)
+            .filter(|&k| {
+                self.step(at, dir, k).is_some_and(|q| {
+                    self.get(q) == Ok(Cell::Empty)
+                        && !extra.contains(&q)
+                        && self.run(at, dir, Piece::Black, &[extra, &[q]].concat()) == 5
+                })
+            })
+            .collect()
+    }
+    // number of fours black has through `at` along `dir`.
+    // an open four (`.bbbb.`) has two ways to make five but is still one four.
+    fn fours_in(&self, at: Point, dir: (isize, isize), extra: &[Point]) -> usize {
+        match self.five_steps(at, dir, extra)[..] {
+            [a, b] if b - a == 5 => 1,
+            ref steps => steps.len(),
+        }
+    }
+    // true iff black at `at` makes a three along `dir`:
+    // one more stone there (that isn't forbidden itself) makes an open four.
+    fn is_three(&self, at: Point, dir: (isize, isize)) -> bool {
+        (-4..=4).filter(|&k| k != 0).any(|k| {
+            let Some(q) = self.step(at, dir, k) else {
+                return false;
+            };
+            if self.get(q) != Ok(Cell::Empty) {
+                return false;
+            }
+            let open_four = matches!(self.five_steps(at, dir, &[q])[..], [a, b] if b - a == 5);
+            open_four && {
+                let mut placed = self.clone();
+                placed.data[at.y][at.x] = Cell::Black;
+                !placed.is_forbidden(&q)
+            }
+        })
+    }
+    /// every empty point `next` may place on.
+    pub fn placeable(&self, next: Piece) -> Vec<Point> {
+        self.points()
+            .filter(|&at| self.get(at) == Ok(Cell::Empty))
+            .filter(|at| next == Piece::White || !self.is_forbidden(at))
+            .collect()
+    }
+    pub fn place(mut self, at: Point, piece: Piece) -> Result<GomokuBoard, PlaceError> {
+        if self.winner().is_some() {
+            return Err(PlaceError::GameOver);
+        }
+        if piece != self.turn() {
+            return Err(PlaceError::NotYourTurn);
+        }
+        match self.get(at) {
+            Err(_) => return Err(PlaceError::OutOfBoundary),
+            Ok(Cell::Empty) => {}
+            Ok(_) => return Err(PlaceError::AlreadyOccupied),
+        }
+        if piece == Piece::Black && self.is_forbidden(&at) {
+            return Err(PlaceError::Forbidden);
+        }
+        self.data[at.y][at.x] = piece.into();
+        Ok(self)
+    }
+
+    /// same format as `othello::Board::decode`, without blocked cells.
+    pub fn decode(
+        serialized: &str,
+        size: usize,
+        rule: GomokuRule,
+    ) -> Result<GomokuBoard, DecodeError> {
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != size {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: size,
+                got: rows.len(),
+            });
+        }
+        let mut board = GomokuBoard::new(size, rule);
+        for (at, line) in rows.into_iter().enumerate() {
+            let chars: Vec<_> = line.chars().collect();
+            if chars.len() != size {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at,
+                    expected: size,
+                    got: chars.len(),
+                });
+            }
+            for (x, char) in chars.into_iter().enumerate() {
+                board.data[at][x] = match char {
+                    '.' | '_' => Cell::Empty,
+                    'b' => Cell::Black,
+                    'w' => Cell::White,
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                };
+            }
+        }
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let rows = self.data.iter().map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    Cell::Black => 'b',
+                    Cell::White => 'w',
+                    _ => '.',
+                })
+                .collect::<String>()
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for GomokuBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    AlreadyOccupied,
+    NotYourTurn,
+    GameOver,
+    /// overline, double four or double three by black in renju.
+    Forbidden,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to place stone. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::NotYourTurn => GameErrorKind::NotYourTurn,
+            PlaceError::GameOver => GameErrorKind::GameOver,
+            PlaceError::Forbidden => GameErrorKind::Forbidden,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn five_in_a_row() {
+        let board = "
+            .......
+            .w.....
+            ..w....
+            bbbw...
+            ....w..
+            ...b.w.
+            .......
+        ";
+        let board = GomokuBoard::decode(board, 7, GomokuRule::FreeStyle).unwrap();
+        assert_eq!(board.winner(), Some(Piece::White));
+        assert!(board.is_over());
+    }
+    #[test]
+    fn overline() {
+        let board = "
+            w.......w
+            .........
+            .........
+            .........
+            bbb.bb..w
+            .........
+            .........
+            .........
+            w.......w
+        ";
+        let free = GomokuBoard::decode(board, 9, GomokuRule::FreeStyle).unwrap();
+        assert!(free.makes_five(Point::new(3, 4), Piece::Black));
+        assert!(!free.is_forbidden(&Point::new(3, 4)));
+        let renju = GomokuBoard::decode(board, 9, GomokuRule::Renju).unwrap();
+        assert!(!renju.makes_five(Point::new(3, 4), Piece::Black));
+        assert!(renju.is_forbidden(&Point::new(3, 4)));
+        let renju = renju.place(Point::new(3, 4), Piece::Black);
+        assert_eq!(renju, Err(PlaceError::Forbidden));
+    }
+    #[test]
+    fn double_three() {
+        let board = "
+            w.......w
+            .........
+            ....b....
+            ....b....
+            ..bb.....
+            .........
+            .........
+            .........
+            w.......w
+        ";
+        let renju = GomokuBoard::decode(board, 9, GomokuRule::Renju).unwrap();
+        assert!(renju.is_forbidden(&Point::new(4, 4)));
+        assert!(!renju.placeable(Piece::Black).contains(&Point::new(4, 4)));
+        // white can play there
+        assert!(renju.placeable(Piece::White).contains(&Point::new(4, 4)));
+        let free = GomokuBoard::decode(board, 9, GomokuRule::FreeStyle).unwrap();
+        assert!(!free.is_forbidden(&Point::new(4, 4)));
+    }
+    #[test]
+    fn four_three_is_allowed() {
+        let board = "
+            w.......w
+            .........
+            ....b....
+            ....b....
+            .bbb.....
+            .........
+            .........
+            .........
+            w.......w
+        ";
+        let renju = GomokuBoard::decode(board, 9, GomokuRule::Renju).unwrap();
+        assert!(!renju.is_forbidden(&Point::new(4, 4)));
+    }
+    #[test]
+    fn double_four() {
+        let board = "
+            w...w...w
+            ....b....
+            ....b....
+            ....b....
+            wbbb.....
+            .........
+            .........
+            .........
+            w.......w
+        ";
+        let renju = GomokuBoard::decode(board, 9, GomokuRule::Renju).unwrap();
+        assert!(renju.is_forbidden(&Point::new(4, 4)));
+    }
+    #[test]
+    fn place_errors() {
+        let board = GomokuBoard::new(9, GomokuRule::Renju);
+        let center = Point::new(4, 4);
+        assert_eq!(
+            board.clone().place(center, Piece::White),
+            Err(PlaceError::NotYourTurn)
+        );
+        assert_eq!(
+            board.clone().place(Point::new(9, 0), Piece::Black),
+            Err(PlaceError::OutOfBoundary)
+        );
+        let board = board.place(center, Piece::Black).unwrap();
+        assert_eq!(board.turn(), Piece::White);
+        assert_eq!(
+            board.place(center, Piece::White),
+            Err(PlaceError::AlreadyOccupied)
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! gravity othello: discs are dropped into a column and fall until they land on something,
+//! like connect four. the landing disc must still capture like in othello.
+//!
+//! this works on a plain `othello::Board`, with y = 0 at the top and the floor below y = height - 1.
+//! blocked cells stop falling discs just like discs do.
+use super::othello::{Board, Cell, OthelloError, Piece, PlaceError, Point};
+
+/// creates a board with the starting four discs at the bottom centre, as they would have fallen there.
+/// ```rust
+/// use boardgame_ai::rules::gravity_othello;
+/// let expected = "
+/// ......
+/// ......
+/// ......
+/// ......
+/// ..bw..
+/// ..wb..
+/// ";
+/// assert_eq!(gravity_othello::new(6, 6).encode(), expected);
+/// ```
+/// panics on sizes `Board::try_new` rejects.
+pub fn new(width: usize, height: usize) -> Board {
+    try_new(width, height).unwrap_or_else(|error| panic!("{}", String::from(error)))
+}
+/// `new`, or an error for the sizes `Board::try_new` rejects.
+pub fn try_new(width: usize, height: usize) -> Result<Board, OthelloError> {
+    let mut board = Board::try_new(width, height)?;
+    let (cx, cy) = (width / 2, height / 2);
+    for (x, y) in [(cx - 1, cy - 1), (cx, cy - 1), (cx - 1, cy), (cx, cy)] {
+        board
+            .set(Point::new(x, y), Cell::Empty)
+            .expect("this shouldn't happen");
+    }
+    let bottom = height - 1;
+    for (x, y, cell) in [
+        (cx - 1, bottom - 1, Cell::Black),
+        (cx, bottom - 1, Cell::White),
+        (cx - 1, bottom, Cell::White),
+        (cx, bottom, Cell::Black),
+    ] {
+        board
+            .set(Point::new(x, y), cell)
+            .expect("this shouldn't happen");
+    }
+    Ok(board)
+}
+
+/// where a disc dropped into `column` would land. None if the column is full or out of the board.
+pub fn landing(board: &Board, column: usize) -> Option<Point> {
+    if column >= board.width {
+        return None;
+    }
+    (0..board.height)
+        .take_while(|&y| board.get(Point::new(column, y)) == Ok(Cell::Empty))
+        .last()
+        .map(|y| Point::new(column, y))
+}
+
+/// count of discs that dropping `piece` into `column` would flip. 0 if it's not a legal move.
+pub fn count_flips(board: &Board, column: usize, piece: Piece) -> usize {
+    landing(board, column).map_or(0, |at| board.count_flips(at, piece))
+}
+
+/// every column `next` can drop into.
+/// ```rust
+/// use boardgame_ai::rules::gravity_othello;
+/// use boardgame_ai::rules::othello::Piece;
+/// let board = gravity_othello::new(6, 6);
+/// assert_eq!(gravity_othello::placeable(&board, Piece::Black), vec![1, 3]);
+/// ```
+pub fn placeable(board: &Board, next: Piece) -> Vec<usize> {
+    (0..board.width)
+        .filter(|&column| count_flips(board, column, next) > 0)
+        .collect()
+}
+
+/// like `Board::legal_mask`, but every cell of a column `next` can drop into is 1,
+/// as clicking anywhere in the column drops the disc.
+pub fn legal_mask(board: &Board, next: Piece) -> Vec<u8> {
+    let columns = placeable(board, next);
+    (0..board.width * board.height)
+        .map(|i| columns.contains(&(i % board.width)) as u8)
+        .collect()
+}
+
+/// drops `piece` into `column` and flips pieces accordingly, using othello's `Board::place`.
+pub fn place(board: Board, column: usize, piece: Piece) -> Result<Board, PlaceError> {
+    // a full (or non-existent) column is reported the way othello reports the cell at its top
+    let at = landing(&board, column).unwrap_or(Point::new(column, 0));
+    board.place(at, piece)
+}
+
+/// true iff neither player can drop anywhere.
+pub fn is_over(board: &Board) -> bool {
+    placeable(board, Piece::Black).is_empty() && placeable(board, Piece::White).is_empty()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn legal_mask_covers_whole_columns() {
+        let board = new(6, 6);
+        let mask = legal_mask(&board, Piece::Black);
+        for (i, &m) in mask.iter().enumerate() {
+            assert_eq!(m == 1, [1, 3].contains(&(i % 6)), "at {}", i);
+        }
+    }
+    #[test]
+    fn falls_to_the_lowest_empty_cell() {
+        let input = "
+            ....
+            .#..
+            ..w.
+            .wwb
+        ";
+        let board = Board::decode(input, 4, 4).unwrap();
+        assert_eq!(landing(&board, 0), Some(Point::new(0, 3)));
+        assert_eq!(landing(&board, 1), Some(Point::new(1, 0))); // stopped by the blocked cell
+        assert_eq!(landing(&board, 2), Some(Point::new(2, 1)));
+        assert_eq!(landing(&board, 3), Some(Point::new(3, 2)));
+        assert_eq!(landing(&board, 4), None);
+        // (0, 3) captures the bottom row, (2, 1) would capture nothing
+        assert_eq!(placeable(&board, Piece::Black), vec![0]);
+        let expected = "
+            ....
+            .#..
+            ..w.
+            bbbb
+        ";
+        let expected = Board::decode(expected, 4, 4).unwrap();
+        assert_eq!(place(board, 0, Piece::Black).unwrap(), expected);
+    }
+    #[test]
+    fn full_column() {
+        let input = "
+            b...
+            w...
+            w...
+            wb..
+        ";
+        let board = Board::decode(input, 4, 4).unwrap();
+        assert_eq!(landing(&board, 0), None);
+        assert!(place(board, 0, Piece::Black).is_err());
+    }
+}
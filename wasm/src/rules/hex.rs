@@ -0,0 +1,375 @@
+//! the game of hex, on an `size` x `size` rhombus of hexagonal cells.
+//!
+//! black moves first and connects the top edge to the bottom edge, white connects left to right.
+//! the board can't fill up without one of them connecting, so there are no draws.
+//! with the swap rule, white's first move may be to take over black's first stone instead of placing,
+//! which keeps black from simply opening in the centre.
+//!
+//! cell `(x, y)` touches `(x ± 1, y)`, `(x, y ± 1)`, `(x + 1, y - 1)` and `(x - 1, y + 1)`.
+//! connections are tracked with a union-find, so checking for a winner is cheap.
+use super::othello::{Cell, Piece, Point};
+use super::{GameError, GameErrorKind};
+use crate::utils::UnionFind;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+pub static HEX_NEIGHBOURS: [(isize, isize); 6] =
+    [(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexMove {
+    Place(Point),
+    /// white takes over black's first stone, mirrored to its own side.
+    Swap,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct HexGameBoard {
+    pub size: usize,
+    /// whether white may swap as its first move.
+    pub swap_rule: bool,
+    swapped: bool,
+    data: Vec<Cell>,
+    // one set per cell, then the top, bottom, left and right edges
+    sets: UnionFind,
+}
+
+#[wasm_bindgen]
+impl HexGameBoard {
+    /// should return Array<Array<"." | "b" | "w">>, row by row. each row is shifted half a cell
+    /// further right than the one above it.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = (0..self.size)
+            .map(|y| {
+                let cells = (0..self.size)
+                    .map(|x| match self.data[self.index(Point::new(x, y))] {
+                        Cell::Black => r#""b""#,
+                        Cell::White => r#""w""#,
+                        _ => r#"".""#,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}]", cells)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    /// the player who moves next. black goes first, and a swap counts as a move.
+    pub fn turn(&self) -> Piece {
+        let stones = self.data.iter().filter(|&&c| c != Cell::Empty).count();
+        if (stones + self.swapped as usize).is_multiple_of(2) {
+            Piece::Black
+        } else {
+            Piece::White
+        }
+    }
+
+    /// true iff white may swap now: the rule is on, and black has placed exactly one stone.
+    pub fn can_swap(&self) -> bool {
+        self.swap_rule
+            && !self.swapped
+            && self.data.iter().filter(|&&c| c == Cell::Black).count() == 1
+            && !self.data.contains(&Cell::White)
+    }
+
+    /// the player who connected their edges, if any.
+    pub fn winner(&self) -> Option<Piece> {
+        let edges = self.size * self.size;
+        if self.sets.connected(edges, edges + 1) {
+            Some(Piece::Black)
+        } else if self.sets.connected(edges + 2, edges + 3) {
+            Some(Piece::White)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+}
+
+impl HexGameBoard {
+    /// creates an empty board. 11 is the classic size.
+    /// ```rust
+    /// use boardgame_ai::rules::hex::*;
+    /// use boardgame_ai::rules::othello::{Piece, Point};
+    /// let board = HexGameBoard::new(3, false);
+    /// let board = board.place(Point::new(1, 1), Piece::Black).unwrap();
+    /// let board = board.place(Point::new(0, 2), Piece::White).unwrap();
+    /// let expected = "
+    /// . . .
+    ///  . b .
+    ///   w . .
+    /// ";
+    /// assert_eq!(board.encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(size: usize, swap_rule: bool) -> Self {
+        Self::try_new(size, swap_rule).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// an empty board. the size must be between 1 and 254.
+    pub fn try_new(size: usize, swap_rule: bool) -> Result<Self, GameError> {
+        if !(1..255).contains(&size) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "size must be between 1 and 254",
+            ));
+        }
+        Ok(HexGameBoard {
+            size,
+            swap_rule,
+            swapped: false,
+            data: vec![Cell::Empty; size * size],
+            sets: UnionFind::new(size * size + 4),
+        })
+    }
+    fn index(&self, at: Point) -> usize {
+        at.y * self.size + at.x
+    }
+    pub fn get(&self, at: Point) -> Result<Cell, String> {
+        if at.x >= self.size || at.y >= self.size {
+            return Err(super::othello::OutOfBoundaryError().into());
+        }
+        Ok(self.data[self.index(at)])
+    }
+    /// every cell, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.size).flat_map(move |y| (0..self.size).map(move |x| Point::new(x, y)))
+    }
+    pub fn neighbours(&self, at: Point) -> impl Iterator<Item = Point> + '_ {
+        HEX_NEIGHBOURS.iter().filter_map(move |&(dx, dy)| {
+            let x = at.x.checked_add_signed(dx)?;
+            let y = at.y.checked_add_signed(dy)?;
+            (x < self.size && y < self.size).then(|| Point::new(x, y))
+        })
+    }
+    // puts a stone without checking the rules, and connects it to its neighbours and edges.
+    fn put(&mut self, at: Point, piece: Piece) {
+        let index = self.index(at);
+        self.data[index] = piece.into();
+        let edges = self.size * self.size;
+        let last = self.size - 1;
+        let touched = match piece {
+            Piece::Black => [(at.y == 0, edges), (at.y == last, edges + 1)],
+            Piece::White => [(at.x == 0, edges + 2), (at.x == last, edges + 3)],
+        };
+        for (touches, edge) in touched {
+            if touches {
+                self.sets.union(index, edge);
+            }
+        }
+        let same: Vec<_> = self
+            .neighbours(at)
+            .filter(|&p| self.data[self.index(p)] == piece.into())
+            .collect();
+        for p in same {
+            let neighbour = self.index(p);
+            self.sets.union(index, neighbour);
+        }
+    }
+    /// every empty cell.
+    pub fn placeable(&self) -> Vec<Point> {
+        self.points()
+            .filter(|&at| self.data[self.index(at)] == Cell::Empty)
+            .collect()
+    }
+    pub fn place(mut self, at: Point, piece: Piece) -> Result<HexGameBoard, PlaceError> {
+        if self.is_over() {
+            return Err(PlaceError::GameOver);
+        }
+        if piece != self.turn() {
+            return Err(PlaceError::NotYourTurn);
+        }
+        match self.get(at) {
+            Err(_) => return Err(PlaceError::OutOfBoundary),
+            Ok(Cell::Empty) => {}
+            Ok(_) => return Err(PlaceError::AlreadyOccupied),
+        }
+        self.put(at, piece);
+        Ok(self)
+    }
+    /// white takes over black's first stone. it's mirrored across the diagonal through `(0, 0)`,
+    /// so it stands in the same relation to white's edges as it did to black's.
+    /// ```rust
+    /// use boardgame_ai::rules::hex::*;
+    /// use boardgame_ai::rules::othello::{Cell, Piece, Point};
+    /// let board = HexGameBoard::new(3, true);
+    /// let board = board.place(Point::new(2, 0), Piece::Black).unwrap();
+    /// let board = board.swap().unwrap();
+    /// assert_eq!(board.get(Point::new(0, 2)), Ok(Cell::White));
+    /// assert_eq!(board.turn(), Piece::Black);
+    /// ```
+    pub fn swap(self) -> Result<HexGameBoard, PlaceError> {
+        if !self.can_swap() {
+            return Err(PlaceError::SwapNotAllowed);
+        }
+        let first = self
+            .points()
+            .find(|&at| self.data[self.index(at)] == Cell::Black)
+            .expect("can_swap checked there is one");
+        let mut swapped = HexGameBoard::new(self.size, self.swap_rule);
+        swapped.swapped = true;
+        swapped.put(Point::new(first.y, first.x), Piece::White);
+        Ok(swapped)
+    }
+    /// plays `play` for the player to move.
+    pub fn play(self, play: HexMove) -> Result<HexGameBoard, PlaceError> {
+        match play {
+            HexMove::Place(at) => {
+                let turn = self.turn();
+                self.place(at, turn)
+            }
+            HexMove::Swap => self.swap(),
+        }
+    }
+
+    /// rows from the top, with or without the indentation `encode` adds. the swap rule is off.
+    pub fn decode(serialized: &str, size: usize) -> Result<HexGameBoard, DecodeError> {
+        let rows: Vec<Vec<char>> = serialized
+            .lines()
+            .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect())
+            .filter(|row: &Vec<char>| !row.is_empty())
+            .collect();
+        if rows.len() != size {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: size,
+                got: rows.len(),
+            });
+        }
+        let mut board = HexGameBoard::new(size, false);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != size {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at: y,
+                    expected: size,
+                    got: row.len(),
+                });
+            }
+            for (x, char) in row.into_iter().enumerate() {
+                match char {
+                    '.' | '_' => {}
+                    'b' => board.put(Point::new(x, y), Piece::Black),
+                    'w' => board.put(Point::new(x, y), Piece::White),
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                }
+            }
+        }
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let rows = (0..self.size).map(|y| {
+            let cells = (0..self.size)
+                .map(|x| match self.data[self.index(Point::new(x, y))] {
+                    Cell::Black => "b",
+                    Cell::White => "w",
+                    _ => ".",
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            " ".repeat(y) + &cells
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for HexGameBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::DecodeError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    AlreadyOccupied,
+    NotYourTurn,
+    GameOver,
+    SwapNotAllowed,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to place stone. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::NotYourTurn => GameErrorKind::NotYourTurn,
+            PlaceError::GameOver => GameErrorKind::GameOver,
+            PlaceError::SwapNotAllowed => GameErrorKind::SwapNotAllowed,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn black_connects_top_to_bottom() {
+        let input = "
+            . b w
+             w b .
+              b . .
+        ";
+        let board = HexGameBoard::decode(input, 3).unwrap();
+        assert_eq!(board.winner(), Some(Piece::Black));
+        // (0, 0) and (1, 1) look diagonal to each other, but they don't touch
+        let input = "
+            b . .
+             . b .
+              . b .
+        ";
+        let board = HexGameBoard::decode(input, 3).unwrap();
+        assert_eq!(board.winner(), None);
+    }
+    #[test]
+    fn white_connects_left_to_right() {
+        let input = "
+            b b .
+             w w .
+              . . b
+        ";
+        let board = HexGameBoard::decode(input, 3).unwrap();
+        assert_eq!(board.winner(), None);
+        let board = board.place(Point::new(2, 1), Piece::White).unwrap();
+        assert_eq!(board.winner(), Some(Piece::White));
+        assert!(board.is_over());
+        assert_eq!(
+            board.place(Point::new(2, 0), Piece::Black),
+            Err(PlaceError::GameOver)
+        );
+    }
+    #[test]
+    fn place_errors() {
+        let board = HexGameBoard::new(3, true);
+        assert_eq!(board.clone().swap(), Err(PlaceError::SwapNotAllowed));
+        assert_eq!(
+            board.clone().place(Point::new(0, 0), Piece::White),
+            Err(PlaceError::NotYourTurn)
+        );
+        assert_eq!(
+            board.clone().place(Point::new(3, 0), Piece::Black),
+            Err(PlaceError::OutOfBoundary)
+        );
+        let board = board.place(Point::new(0, 0), Piece::Black).unwrap();
+        assert!(board.can_swap());
+        assert_eq!(
+            board.clone().place(Point::new(0, 0), Piece::White),
+            Err(PlaceError::AlreadyOccupied)
+        );
+        let board = board.place(Point::new(1, 1), Piece::White).unwrap();
+        assert!(!board.can_swap());
+        let board = HexGameBoard::new(3, false)
+            .place(Point::new(0, 0), Piece::Black)
+            .unwrap();
+        assert!(!board.can_swap());
+    }
+}
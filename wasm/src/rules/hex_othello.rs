@@ -0,0 +1,406 @@
+//! othello on a hexagon-shaped board made of hexagonal cells.
+//!
+//! cells are addressed by axial coordinates `(q, r)`, with the centre at `(0, 0)`.
+//! a board of radius `R` contains every cell with `|q| <= R`, `|r| <= R` and `|q + r| <= R`.
+//! lines go in six directions instead of eight, otherwise the rules are the same as othello.
+use super::othello::{Cell, DecodeError, Piece};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+pub static SIX_DIRECTIONS: [(isize, isize); 6] =
+    [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[wasm_bindgen]
+pub struct HexPoint {
+    pub q: isize,
+    pub r: isize,
+}
+impl HexPoint {
+    pub fn new(q: isize, r: isize) -> Self {
+        HexPoint { q, r }
+    }
+    /// the implicit third axial coordinate, so that `q + r + s == 0`.
+    pub fn s(&self) -> isize {
+        -self.q - self.r
+    }
+    /// distance from the centre, in cells.
+    pub fn ring(&self) -> usize {
+        self.q
+            .unsigned_abs()
+            .max(self.r.unsigned_abs())
+            .max(self.s().unsigned_abs())
+    }
+    pub fn move_for(&self, (dq, dr): (isize, isize), times: isize) -> Self {
+        HexPoint {
+            q: self.q + dq * times,
+            r: self.r + dr * times,
+        }
+    }
+}
+#[wasm_bindgen]
+impl HexPoint {
+    pub fn create(q: isize, r: isize) -> HexPoint {
+        HexPoint::new(q, r)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct HexBoard {
+    // private, as `data` is laid out by it. JS reads it through `radius()`.
+    radius: usize,
+    // indexed by [r + radius][q + radius]. cells outside of the hexagon are never read nor written.
+    data: Vec<Vec<Cell>>,
+}
+
+#[wasm_bindgen]
+impl HexBoard {
+    /// how many cells the hexagon reaches out from the centre.
+    #[wasm_bindgen(getter)]
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+    /// should return Array<{ q: number, r: number, cell: "." | "b" | "w" }>, row by row from the top.
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data = self
+            .points()
+            .map(|point| {
+                let cell = match self.get(point) {
+                    Ok(Cell::Black) => "b",
+                    Ok(Cell::White) => "w",
+                    _ => ".",
+                };
+                format!(r#"{{"q":{},"r":{},"cell":"{}"}}"#, point.q, point.r, cell)
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    pub fn score(&self, player: Piece) -> usize {
+        let player_cell: Cell = player.into();
+        self.points()
+            .filter(|&point| self.get(point) == Ok(player_cell))
+            .count()
+    }
+
+    /// true iff neither player can place anymore.
+    pub fn is_over(&self) -> bool {
+        self.placeable(Piece::Black).is_empty() && self.placeable(Piece::White).is_empty()
+    }
+
+    /// who has more discs. None if it's a tie.
+    pub fn winner(&self) -> Option<Piece> {
+        let black = self.score(Piece::Black);
+        let white = self.score(Piece::White);
+        match black.cmp(&white) {
+            std::cmp::Ordering::Equal => None,
+            std::cmp::Ordering::Greater => Some(Piece::Black),
+            std::cmp::Ordering::Less => Some(Piece::White),
+        }
+    }
+}
+
+impl HexBoard {
+    /// creates a board with the first ring around the (empty) centre alternately filled.
+    /// ```rust
+    /// use boardgame_ai::rules::hex_othello::HexBoard;
+    /// let expected = "
+    ///   . . .
+    ///  . w b .
+    /// . b . w .
+    ///  . w b .
+    ///   . . .
+    /// ";
+    /// assert_eq!(HexBoard::new(2).encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(radius: usize) -> Self {
+        Self::try_new(radius).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// the starting board. the radius must be between 2 and 63.
+    pub fn try_new(radius: usize) -> Result<Self, GameError> {
+        if !(2..64).contains(&radius) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "radius must be between 2 and 63",
+            ));
+        }
+        let mut new = Self {
+            radius,
+            data: vec![vec![Cell::Empty; radius * 2 + 1]; radius * 2 + 1],
+        };
+        let center = HexPoint::new(0, 0);
+        for (i, &dir) in SIX_DIRECTIONS.iter().enumerate() {
+            let piece = if i % 2 == 0 { Cell::White } else { Cell::Black };
+            new.set(center.move_for(dir, 1), piece)
+                .expect("this shouldn't happen");
+        }
+        Ok(new)
+    }
+    pub fn contains(&self, at: HexPoint) -> bool {
+        at.ring() <= self.radius
+    }
+    /// every cell on the board, row by row (r ascending), then q ascending.
+    pub fn points(&self) -> impl Iterator<Item = HexPoint> + '_ {
+        let radius = self.radius as isize;
+        (-radius..=radius).flat_map(move |r| {
+            let from = (-radius).max(-r - radius);
+            let to = radius.min(-r + radius);
+            (from..=to).map(move |q| HexPoint::new(q, r))
+        })
+    }
+    fn index(&self, at: HexPoint) -> Result<(usize, usize), OutOfBoundaryError> {
+        if !self.contains(at) {
+            return Err(OutOfBoundaryError());
+        }
+        let radius = self.radius as isize;
+        Ok(((at.r + radius) as usize, (at.q + radius) as usize))
+    }
+    pub fn get(&self, at: HexPoint) -> Result<Cell, OutOfBoundaryError> {
+        let (row, col) = self.index(at)?;
+        Ok(self.data[row][col])
+    }
+    /// primitive operation. doesn't do anything other than setting the piece.
+    pub fn set(&mut self, at: HexPoint, cell: Cell) -> Result<(), OutOfBoundaryError> {
+        let (row, col) = self.index(at)?;
+        self.data[row][col] = cell;
+        Ok(())
+    }
+
+    fn count_flips_in_direction(&self, at: HexPoint, piece: Piece, dir: (isize, isize)) -> usize {
+        let mut flipping = 0;
+        loop {
+            let Ok(cell) = self.get(at.move_for(dir, flipping as isize + 1)) else {
+                return 0;
+            };
+            if cell == piece.into() && flipping > 0 {
+                return flipping;
+            }
+            if cell != piece.flip().into() {
+                return 0;
+            }
+            flipping += 1;
+        }
+    }
+    pub fn count_flips(&self, at: HexPoint, piece: Piece) -> usize {
+        if self.get(at) != Ok(Cell::Empty) {
+            return 0;
+        }
+        SIX_DIRECTIONS
+            .iter()
+            .map(|&dir| self.count_flips_in_direction(at, piece, dir))
+            .sum()
+    }
+    /// ```rust
+    /// use boardgame_ai::rules::hex_othello::*;
+    /// use boardgame_ai::rules::othello::Piece;
+    /// let board = HexBoard::new(2);
+    /// assert_eq!(
+    ///     board.placeable(Piece::Black),
+    ///     vec![
+    ///         HexPoint::new(1, -2),
+    ///         HexPoint::new(-1, -1),
+    ///         HexPoint::new(2, -1),
+    ///         HexPoint::new(-2, 1),
+    ///         HexPoint::new(1, 1),
+    ///         HexPoint::new(-1, 2),
+    ///     ],
+    /// );
+    /// ```
+    pub fn placeable(&self, next: Piece) -> Vec<HexPoint> {
+        self.points()
+            .filter(|&point| self.count_flips(point, next) > 0)
+            .collect()
+    }
+    pub fn place(mut self, at: HexPoint, piece: Piece) -> Result<HexBoard, PlaceError> {
+        match self.get(at) {
+            Err(_) => return Err(PlaceError::OutOfBoundary),
+            Ok(Cell::Empty) => {}
+            Ok(_) => return Err(PlaceError::AlreadyOccupied),
+        }
+        let flips: Vec<_> = SIX_DIRECTIONS
+            .iter()
+            .map(|&dir| (dir, self.count_flips_in_direction(at, piece, dir)))
+            .collect();
+        if flips.iter().all(|(_, count)| *count == 0) {
+            return Err(PlaceError::NoPiecesChanged);
+        }
+        self.set(at, piece.into()).unwrap(); // checked above
+        for (dir, count) in flips {
+            for i in 1..=count {
+                self.set(at.move_for(dir, i as isize), piece.into())
+                    .unwrap(); // counted before, so it's on the board
+            }
+        }
+        Ok(self)
+    }
+
+    /// one line per row of the hexagon, indented so that it looks like one.
+    /// whitespace is ignored, so rows can be written without indentation too.
+    /// ```rust
+    /// use boardgame_ai::rules::hex_othello::HexBoard;
+    /// let serialized = "
+    ///   . . .
+    ///  . w b .
+    /// . b w w .
+    ///  . w b .
+    ///   . . b
+    /// ";
+    /// let board = HexBoard::decode(serialized, 2).unwrap();
+    /// assert_eq!(board.encode(), serialized);
+    /// ```
+    pub fn decode(serialized: &str, radius: usize) -> Result<HexBoard, DecodeError> {
+        let rows: Vec<Vec<char>> = serialized
+            .lines()
+            .map(|line| line.chars().filter(|c| !c.is_whitespace()).collect())
+            .filter(|row: &Vec<char>| !row.is_empty())
+            .collect();
+        let size = radius * 2 + 1;
+        if rows.len() != size {
+            return Err(DecodeError::UnmatchedOverallLength {
+                expected: size,
+                got: rows.len(),
+            });
+        }
+        let mut board = HexBoard {
+            radius,
+            data: vec![vec![Cell::Empty; size]; size],
+        };
+        for (at, row) in rows.into_iter().enumerate() {
+            let r = at as isize - radius as isize;
+            let points: Vec<_> = board.points().filter(|point| point.r == r).collect();
+            if row.len() != points.len() {
+                return Err(DecodeError::UnmatchedLocalLength {
+                    at,
+                    expected: points.len(),
+                    got: row.len(),
+                });
+            }
+            for (point, char) in points.into_iter().zip(row) {
+                let cell = match char {
+                    '.' | '_' => Cell::Empty,
+                    'w' => Cell::White,
+                    'b' => Cell::Black,
+                    _ => return Err(DecodeError::UnknownChar(char)),
+                };
+                board.set(point, cell).unwrap(); // points come from the board itself
+            }
+        }
+        Ok(board)
+    }
+    pub fn encode(&self) -> String {
+        let radius = self.radius as isize;
+        let rows = (-radius..=radius).map(|r| {
+            let cells = self
+                .points()
+                .filter(|point| point.r == r)
+                .map(|point| match self.get(point) {
+                    Ok(Cell::Black) => "b",
+                    Ok(Cell::White) => "w",
+                    _ => ".",
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            " ".repeat(r.unsigned_abs()) + &cells
+        });
+        "\n".to_string() + &rows.collect::<Vec<_>>().join("\n") + "\n"
+    }
+}
+impl fmt::Debug for HexBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+pub use super::othello::OutOfBoundaryError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    AlreadyOccupied,
+    NoPiecesChanged,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to place piece at board. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::NoPiecesChanged => GameErrorKind::NoPiecesChanged,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn place_six_directions() {
+        let input = "
+               . . . .
+              . b . b .
+             . . w w . .
+            . b w . w b .
+             . . w w . .
+              . b . b .
+               . . . .
+        ";
+        let expected = "
+               . . . .
+              . b . b .
+             . . b b . .
+            . b b b b b .
+             . . b b . .
+              . b . b .
+               . . . .
+        ";
+        let board = HexBoard::decode(input, 3).unwrap();
+        let expected = HexBoard::decode(expected, 3).unwrap();
+        assert_eq!(board.count_flips(HexPoint::new(0, 0), Piece::Black), 6);
+        let board = board.place(HexPoint::new(0, 0), Piece::Black).unwrap();
+        assert_eq!(board, expected);
+    }
+    #[test]
+    fn decode_wrong_shape() {
+        let input = "
+            . . .
+            . . . .
+            . . . .
+            . . . .
+            . . .
+        ";
+        assert_eq!(
+            HexBoard::decode(input, 2),
+            Err(DecodeError::UnmatchedLocalLength {
+                at: 2,
+                expected: 5,
+                got: 4
+            })
+        );
+    }
+    #[test]
+    fn errors() {
+        let board = HexBoard::new(2);
+        assert_eq!(
+            board.clone().place(HexPoint::new(2, 2), Piece::Black),
+            Err(PlaceError::OutOfBoundary)
+        );
+        assert_eq!(
+            board.clone().place(HexPoint::new(1, 0), Piece::Black),
+            Err(PlaceError::AlreadyOccupied)
+        );
+        assert_eq!(
+            board.clone().place(HexPoint::new(0, 0), Piece::Black),
+            Err(PlaceError::NoPiecesChanged)
+        );
+        assert_eq!(board.placeable(Piece::White).len(), 6);
+    }
+}
@@ -1 +1,114 @@
+pub mod ataxx;
+pub mod connect_four;
+pub mod cube_othello;
+pub mod draughts;
+pub mod gomoku;
+pub mod gravity_othello;
+pub mod hex;
+pub mod hex_othello;
 pub mod othello;
+pub mod rolit;
+
+use wasm_bindgen::prelude::*;
+
+/// what went wrong, for JS. the othello variants throw `othello::OthelloError` instead,
+/// which also tells where and for whom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[wasm_bindgen]
+pub enum GameErrorKind {
+    /// the board's size is out of what the game supports. see each board's `try_new`.
+    InvalidSize,
+    /// rolit is played by 2 to 4.
+    InvalidPlayers,
+    /// a serialized board that couldn't be read.
+    InvalidBoard,
+    BadNotation,
+    OutOfBoundary,
+    AlreadyOccupied,
+    Blocked,
+    NoPiecesChanged,
+    NotAdjacent,
+    MustCapture,
+    NotPlaying,
+    ColumnFull,
+    NotYourTurn,
+    NotYourPiece,
+    GameOver,
+    Forbidden,
+    TooFar,
+    SwapNotAllowed,
+    IllegalMove,
+}
+
+/// the error the games other than othello throw to JS, instead of a string or a crash.
+/// each game's `PlaceError` converts into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct GameError {
+    kind: GameErrorKind,
+    message: String,
+}
+#[wasm_bindgen]
+impl GameError {
+    pub fn kind(&self) -> GameErrorKind {
+        self.kind
+    }
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+impl GameError {
+    pub(crate) fn new(kind: GameErrorKind, message: &str) -> Self {
+        GameError {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+impl From<GameError> for String {
+    fn from(error: GameError) -> String {
+        error.message
+    }
+}
+impl From<othello::DecodeError> for GameError {
+    fn from(error: othello::DecodeError) -> Self {
+        GameError::new(GameErrorKind::InvalidBoard, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn sizes_are_errors_not_panics() {
+        let kind = |result: Result<(), GameError>| result.unwrap_err().kind();
+        let size = GameErrorKind::InvalidSize;
+        assert_eq!(
+            kind(rolit::RolitBoard::try_new(6, 6, 5).map(|_| ())),
+            GameErrorKind::InvalidPlayers
+        );
+        assert_eq!(kind(rolit::RolitBoard::try_new(7, 6, 4).map(|_| ())), size);
+        assert_eq!(kind(hex_othello::HexBoard::try_new(1).map(|_| ())), size);
+        assert_eq!(kind(cube_othello::CubeBoard::try_new(5).map(|_| ())), size);
+        assert_eq!(
+            kind(connect_four::ConnectFourBoard::try_new(20, 20, 4).map(|_| ())),
+            size
+        );
+        assert_eq!(
+            kind(gomoku::GomokuBoard::try_new(4, gomoku::GomokuRule::FreeStyle).map(|_| ())),
+            size
+        );
+        assert_eq!(kind(ataxx::AtaxxBoard::try_new(1, 5).map(|_| ())), size);
+        assert_eq!(kind(hex::HexGameBoard::try_new(0, true).map(|_| ())), size);
+        assert!(connect_four::ConnectFourBoard::try_new(7, 6, 4).is_ok());
+        assert_eq!(cube_othello::CubeBoard::try_new(4).unwrap().size(), 4);
+        assert_eq!(hex_othello::HexBoard::try_new(3).unwrap().radius(), 3);
+    }
+    #[test]
+    fn place_errors_convert() {
+        let board = connect_four::ConnectFourBoard::new(4, 4, 4);
+        let error = GameError::from(board.place(9, connect_four::Disc::Red).unwrap_err());
+        assert_eq!(error.kind(), GameErrorKind::OutOfBoundary);
+        assert!(error.message().contains("OutOfBoundary"));
+    }
+}
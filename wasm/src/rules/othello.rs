@@ -1,7 +1,7 @@
 use std::fmt;
 use wasm_bindgen::prelude::*;
 
-static EIGHT_DIRECTIONS: [(isize, isize); 8] = [
+pub(crate) static EIGHT_DIRECTIONS: [(isize, isize); 8] = [
     (-1, -1),
     (0, -1),
     (1, -1),
@@ -36,6 +36,43 @@ impl Point {
         };
         Ok(res)
     }
+    /// standard othello notation: the column as a letter from `a`, then the row from 1.
+    /// columns past `z` go on like spreadsheets do: `aa`, `ab`, ... `az`, `ba`, ...
+    /// ```rust
+    /// use boardgame_ai::rules::othello::Point;
+    /// assert_eq!(Point::new(5, 4).notation(), "f5");
+    /// assert_eq!(Point::new(26, 0).notation(), "aa1");
+    /// assert_eq!(Point::from_notation("F5"), Some(Point::new(5, 4)));
+    /// assert_eq!(Point::from_notation("f0"), None);
+    /// ```
+    pub fn notation(&self) -> String {
+        let mut column = Vec::new();
+        let mut n = self.x + 1;
+        while n > 0 {
+            column.push(b'a' + ((n - 1) % 26) as u8);
+            n = (n - 1) / 26;
+        }
+        column.reverse();
+        format!("{}{}", String::from_utf8_lossy(&column), self.y + 1)
+    }
+    /// parses `notation`, in either case. doesn't check the board size.
+    pub fn from_notation(notation: &str) -> Option<Point> {
+        let notation = notation.trim();
+        let letters = notation
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(notation.len());
+        if letters == 0 {
+            return None;
+        }
+        let mut x: usize = 0;
+        for c in notation[..letters].bytes() {
+            let digit = (c.to_ascii_lowercase() - b'a') as usize + 1;
+            x = x.checked_mul(26)?.checked_add(digit)?;
+        }
+        let row: usize = notation[letters..].parse().ok()?;
+        let y = row.checked_sub(1)?;
+        Some(Point::new(x - 1, y))
+    }
 }
 
 #[wasm_bindgen]
@@ -50,6 +87,9 @@ pub struct Direction {
     y: isize,
 }
 impl Direction {
+    pub fn new(x: isize, y: isize) -> Self {
+        Direction { x, y }
+    }
     pub fn times(&self, times: isize) -> Self {
         Self {
             x: self.x * times,
@@ -66,8 +106,34 @@ mod test_point {
         let pt = Point { x: 0, y: 0 };
         pt.move_for(dir).ok();
     }
+    #[test]
+    fn notation_round_trip() {
+        for (x, y) in [
+            (0, 0),
+            (7, 7),
+            (3, 11),
+            (25, 0),
+            (26, 3),
+            (51, 0),
+            (52, 9),
+            (253, 253),
+        ]
+        .iter()
+        .copied()
+        {
+            let point = Point::new(x, y);
+            assert_eq!(Point::from_notation(&point.notation()), Some(point));
+        }
+        assert_eq!(Point::from_notation("a1"), Some(Point::new(0, 0)));
+        assert_eq!(Point::from_notation("h8"), Some(Point::new(7, 7)));
+        assert_eq!(Point::from_notation(""), None);
+        assert_eq!(Point::from_notation("5f"), None);
+        assert_eq!(Point::from_notation("pass"), None);
+        assert_eq!(Point::from_notation("AZ2"), Some(Point::new(51, 1)));
+        assert_eq!(Point::new(253, 0).notation(), "it1");
+    }
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[wasm_bindgen]
 pub enum Piece {
     Black,
@@ -89,17 +155,20 @@ impl From<Piece> for Cell {
         }
     }
 }
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[wasm_bindgen]
 pub enum Cell {
     Empty,
     Black,
     White,
+    /// a hole in the board. nobody can place on it, and lines are cut at it.
+    Blocked,
 }
 impl Cell {
     pub fn flip(&self) -> Cell {
         match self {
             Cell::Empty => Cell::Empty,
+            Cell::Blocked => Cell::Blocked,
             Cell::Black => Cell::White,
             Cell::White => Cell::Black,
         }
@@ -108,23 +177,49 @@ impl Cell {
 impl From<Cell> for Option<Piece> {
     fn from(val: Cell) -> Self {
         match val {
-            Cell::Empty => None,
+            Cell::Empty | Cell::Blocked => None,
             Cell::Black => Some(Piece::Black),
             Cell::White => Some(Piece::White),
         }
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+/// which side wins when the game is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[wasm_bindgen]
+pub enum WinCondition {
+    /// normal othello.
+    #[default]
+    MostDiscs,
+    /// anti-othello (misère). the player with fewer discs wins.
+    FewestDiscs,
+}
+
+/// how the edges of the board connect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+#[wasm_bindgen]
+pub enum Topology {
+    /// normal othello. lines end at the edges.
+    #[default]
+    Flat,
+    /// lines wrap around the edges, so left connects to right and top connects to bottom.
+    Torus,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
 #[wasm_bindgen]
 pub struct Board {
-    pub size: usize,
+    pub width: usize,
+    pub height: usize,
+    pub win_condition: WinCondition,
+    pub topology: Topology,
     data: Vec<Vec<Cell>>,
 }
 #[wasm_bindgen]
 impl Board {
     /// is an (relatively) expensive operation, so better cached than done every access
-    /// should return Array<Array<"." | "b" | "w">>
+    /// should return Array<Array<"." | "b" | "w" | "#">>
+    /// prefer `cell_bytes` for rendering, which skips the JSON round trip.
     pub fn get_data(&self) -> wasm_bindgen::JsValue {
         let data: String = self
             .data
@@ -132,12 +227,13 @@ impl Board {
             .map(|row| {
                 format!(
                     "[{}]",
-                    &row.iter()
+                    row.iter()
                         .map(|p| {
                             match p {
                                 Cell::Empty => r#"".""#,
                                 Cell::Black => r#""b""#,
                                 Cell::White => r#""w""#,
+                                Cell::Blocked => r##""#""##,
                             }
                         })
                         .collect::<Vec<_>>()
@@ -149,8 +245,60 @@ impl Board {
         js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
     }
 
-    pub fn size(&self) -> usize {
-        self.size
+    /// every cell as a byte, row by row, so JS gets a `Uint8Array` instead of parsing strings.
+    /// the bytes are `Cell`'s values: `cell_bytes()[y * width + x] === Cell.Black` and so on.
+    /// ```rust
+    /// use boardgame_ai::rules::othello::{Board, Cell};
+    /// let board = Board::decode("#.\nbw", 2, 2).unwrap();
+    /// let bytes = [Cell::Blocked, Cell::Empty, Cell::Black, Cell::White].map(|c| c as u8);
+    /// assert_eq!(board.cell_bytes(), bytes);
+    /// ```
+    pub fn cell_bytes(&self) -> Vec<u8> {
+        self.data
+            .iter()
+            .flat_map(|row| row.iter().map(|&cell| cell as u8))
+            .collect()
+    }
+
+    /// 1 where `player` can place and 0 elsewhere, laid out like `cell_bytes`.
+    pub fn legal_mask(&self, player: Piece) -> Vec<u8> {
+        let mut mask = vec![0; self.width * self.height];
+        for at in self.placeable(player) {
+            mask[at.y * self.width + at.x] = 1;
+        }
+        mask
+    }
+
+    /// what the last move changed, laid out like `cell_bytes`:
+    /// 2 where a disc was placed, 1 where one was flipped and 0 elsewhere.
+    /// `before` should be the board before the move, of the same size.
+    /// ```rust
+    /// use boardgame_ai::rules::othello::{Board, Piece};
+    /// let before = Board::new(4, 4);
+    /// let at = before.placeable(Piece::Black)[0];
+    /// let after = before.clone().place(at, Piece::Black).unwrap();
+    /// let mask = after.flip_mask(&before);
+    /// assert_eq!(mask[at.y * 4 + at.x], 2);
+    /// assert_eq!(mask.iter().filter(|&&m| m == 1).count(), 1);
+    /// ```
+    pub fn flip_mask(&self, before: &Board) -> Vec<u8> {
+        self.cell_bytes()
+            .into_iter()
+            .zip(before.cell_bytes())
+            .map(|(now, then)| match (then, now) {
+                (then, now) if then == now => 0,
+                (then, _) if then == Cell::Empty as u8 => 2,
+                _ => 1,
+            })
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
     }
 
     pub fn score(&self, player: Piece) -> usize {
@@ -160,10 +308,57 @@ impl Board {
             .map(|row| row.iter().filter(|&&cell| cell == player_cell).count())
             .sum()
     }
+
+    /// true iff neither player can place anymore.
+    pub fn is_over(&self) -> bool {
+        self.placeable(Piece::Black).is_empty() && self.placeable(Piece::White).is_empty()
+    }
+
+    /// who is ahead according to `win_condition`. None if it's a tie.
+    /// this doesn't check if the game is over; use `is_over` for that.
+    pub fn winner(&self) -> Option<Piece> {
+        let black = self.score(Piece::Black);
+        let white = self.score(Piece::White);
+        let leader = match black.cmp(&white) {
+            std::cmp::Ordering::Equal => return None,
+            std::cmp::Ordering::Greater => Piece::Black,
+            std::cmp::Ordering::Less => Piece::White,
+        };
+        match self.win_condition {
+            WinCondition::MostDiscs => Some(leader),
+            WinCondition::FewestDiscs => Some(leader.flip()),
+        }
+    }
 }
 
 impl Board {
-    // returns all cells (either Cell::Empty, Cell::Black, or Cell::White) in order
+    pub fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+    /// moves `at` by `d`, following the board's topology.
+    /// returns Err iff the result is off the board, which never happens on a torus.
+    pub fn move_for(&self, at: Point, d: Direction) -> Result<Point, OutOfBoundaryError> {
+        match self.topology {
+            Topology::Flat => {
+                let res = at.move_for(d)?;
+                if res.x < self.width && res.y < self.height {
+                    Ok(res)
+                } else {
+                    Err(OutOfBoundaryError())
+                }
+            }
+            Topology::Torus => Ok(Point {
+                x: (at.x as isize + d.x).rem_euclid(self.width as isize) as usize,
+                y: (at.y as isize + d.y).rem_euclid(self.height as isize) as usize,
+            }),
+        }
+    }
+    // returns all cells (either Cell::Empty, Cell::Black, Cell::White, or Cell::Blocked) in order
     pub fn cells(self) -> Vec<(Point, Cell)> {
         self.data
             .into_iter()
@@ -196,22 +391,62 @@ impl Board {
             .ok_or(OutOfBoundaryError())?
             .to_owned())
     }
-    pub fn new(size: usize) -> Self {
-        assert!(size.is_multiple_of(2), "size must be divisible by 2");
-        assert!(size < 255, "size should not be larger than 255");
+    /// creates a `width` x `height` board with the starting four discs at the centre.
+    /// ```rust
+    /// use boardgame_ai::rules::othello::Board;
+    /// let expected = "
+    /// ......
+    /// ......
+    /// ......
+    /// ..bw..
+    /// ..wb..
+    /// ......
+    /// ......
+    /// ......
+    /// ";
+    /// assert_eq!(Board::new(6, 8).encode(), expected);
+    /// ```
+    /// panics on sizes `try_new` rejects.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::try_new(width, height).unwrap_or_else(|error| panic!("{}", error.message))
+    }
+    /// the starting board. width and height must be even, and between 2 and 254.
+    /// ```rust
+    /// use boardgame_ai::rules::othello::*;
+    /// assert!(Board::try_new(8, 6).is_ok());
+    /// let error = Board::try_new(7, 8).unwrap_err();
+    /// assert_eq!(error.kind(), OthelloErrorKind::InvalidSize);
+    /// ```
+    pub fn try_new(width: usize, height: usize) -> Result<Self, OthelloError> {
+        if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(OthelloError::new(
+                OthelloErrorKind::InvalidSize,
+                "width and height must be divisible by 2",
+            ));
+        }
+        if !(2..255).contains(&width) || !(2..255).contains(&height) {
+            return Err(OthelloError::new(
+                OthelloErrorKind::InvalidSize,
+                "width and height should be between 2 and 254",
+            ));
+        }
         let mut new = Self {
-            size,
-            data: vec![vec![Cell::Empty; size]; size],
+            width,
+            height,
+            win_condition: WinCondition::default(),
+            topology: Topology::default(),
+            data: vec![vec![Cell::Empty; width]; height],
         };
-        new.set(Point::new(size / 2 - 1, size / 2 - 1), Cell::Black)
+        let (cx, cy) = (width / 2, height / 2);
+        new.set(Point::new(cx - 1, cy - 1), Cell::Black)
             .expect("this shouldn't happen");
-        new.set(Point::new(size / 2 - 1, size / 2), Cell::White)
+        new.set(Point::new(cx - 1, cy), Cell::White)
             .expect("this shouldn't happen");
-        new.set(Point::new(size / 2, size / 2 - 1), Cell::White)
+        new.set(Point::new(cx, cy - 1), Cell::White)
             .expect("this shouldn't happen");
-        new.set(Point::new(size / 2, size / 2), Cell::Black)
+        new.set(Point::new(cx, cy), Cell::Black)
             .expect("this shouldn't happen");
-        new
+        Ok(new)
     }
     /// flips pieces accordingly. returns Ok(count of flipped pieces) or Err(PlaceError).
     /// ```rust
@@ -228,8 +463,8 @@ impl Board {
     ///     ....
     ///     ....
     /// ";
-    /// let board = Board::decode(input, 4).unwrap();
-    /// let expected = Board::decode(expected, 4).unwrap();
+    /// let board = Board::decode(input, 4, 4).unwrap();
+    /// let expected = Board::decode(expected, 4, 4).unwrap();
     ///
     /// let next_board = board.place(Point::new(0, 0), Piece::Black).unwrap();
     /// assert_eq!(next_board, expected);
@@ -243,6 +478,14 @@ impl Board {
                 kind: PlaceErrorKind::OutOfBoundary,
             });
         };
+        if prev == Cell::Blocked {
+            return Err(PlaceError {
+                board: self,
+                at,
+                player: piece,
+                kind: PlaceErrorKind::Blocked,
+            });
+        }
         if prev != Cell::Empty {
             return Err(PlaceError {
                 board: self,
@@ -251,12 +494,14 @@ impl Board {
                 kind: PlaceErrorKind::AlreadyOccupied,
             });
         }
-        self.set(at, piece.into()).unwrap(); // shouldn't panic, as it would be already be return beforehand
-        let mut count = 0;
-        for dir in EIGHT_DIRECTIONS.iter().map(|&(x, y)| Direction { x, y }) {
-            count += flip_in_direction(&mut self, at, piece, dir);
-        }
-        if count == 0 {
+        // counted on the board as it is, as lines can cross each other on a torus,
+        // and a disc flipped for one direction mustn't close a line in another
+        let flips: Vec<_> = EIGHT_DIRECTIONS
+            .iter()
+            .map(|&(x, y)| Direction { x, y })
+            .map(|dir| (dir, count_planned_flip_in_direction(&self, at, piece, dir)))
+            .collect();
+        if flips.iter().all(|(_, count)| *count == 0) {
             return Err(PlaceError {
                 board: self,
                 at,
@@ -264,6 +509,13 @@ impl Board {
                 kind: PlaceErrorKind::NoPiecesChanged,
             });
         }
+        self.set(at, piece.into()).unwrap(); // shouldn't panic, as it would be already be return beforehand
+        for (dir, count) in flips {
+            for i in 1..=count {
+                let pos = self.move_for(at, dir.times(i as isize)).unwrap(); // counted before, so it's on the board
+                self.set(pos, piece.into()).unwrap();
+            }
+        }
         Ok(self)
     }
     pub fn count_flips(&self, at: Point, piece: Piece) -> usize {
@@ -271,7 +523,7 @@ impl Board {
             return 0; // can't place when it's out of the board
         };
         if prev != Cell::Empty {
-            return 0; // can't place when it's already occupied
+            return 0; // can't place when it's already occupied or blocked
         }
         let mut flippable = 0;
         for dir in EIGHT_DIRECTIONS.iter().map(|&(x, y)| Direction { x, y }) {
@@ -280,28 +532,30 @@ impl Board {
         flippable
     }
 
+    /// parses a board. any setup is accepted, so this is also how custom starting positions are made.
+    /// `.` (or `_`) is an empty cell, `b` / `w` are discs, and `#` is a blocked cell.
     /// ```rust
     /// use boardgame_ai::rules::othello::Board;
     /// let serialized = "
-    /// ......
+    /// #....#
     /// ..w...
     /// ..wb..
     /// .wwb..
     /// ...b..
-    /// ......
+    /// #....#
     /// ";
-    /// let board = Board::decode(serialized, 6).unwrap();
+    /// let board = Board::decode(serialized, 6, 6).unwrap();
     /// assert_eq!(board.encode(), serialized);
     /// ```
-    pub fn decode(serialized: &str, board_size: usize) -> Result<Board, DecodeError> {
+    pub fn decode(serialized: &str, width: usize, height: usize) -> Result<Board, DecodeError> {
         let rows: Vec<_> = serialized
             .lines()
             .map(|line| line.trim())
             .filter(|line| !line.is_empty())
             .collect();
-        if rows.len() != board_size {
+        if rows.len() != height {
             return Err(DecodeError::UnmatchedOverallLength {
-                expected: board_size,
+                expected: height,
                 got: rows.len(),
             });
         }
@@ -315,26 +569,30 @@ impl Board {
                         '.' | '_' => Ok(Cell::Empty), // _ can be used to emphasize cells
                         'w' => Ok(Cell::White),
                         'b' => Ok(Cell::Black),
+                        '#' => Ok(Cell::Blocked),
                         _ => Err(DecodeError::UnknownChar(char)),
                     })
                     .collect::<Result<Vec<_>, _>>()?;
-                if row.len() == board_size {
+                if row.len() == width {
                     Ok(row)
                 } else {
                     Err(DecodeError::UnmatchedLocalLength {
                         at,
-                        expected: board_size,
+                        expected: width,
                         got: row.len(),
                     })
                 }
             })
             .collect::<Result<Vec<_>, _>>()?;
-        assert_eq!(rows.len(), board_size);
+        assert_eq!(rows.len(), height);
         for row in rows.iter() {
-            assert_eq!(row.len(), board_size);
+            assert_eq!(row.len(), width);
         }
         Ok(Board {
-            size: board_size,
+            width,
+            height,
+            win_condition: WinCondition::default(),
+            topology: Topology::default(),
             data: rows,
         })
     }
@@ -349,6 +607,7 @@ impl Board {
                             Cell::Empty => ".",
                             Cell::Black => "b",
                             Cell::White => "w",
+                            Cell::Blocked => "#",
                         })
                         .collect::<Vec<&str>>()
                         .concat()
@@ -368,7 +627,7 @@ impl Board {
     /// .b_b..
     /// ......
     /// ";
-    /// let board = Board::decode(serialized, 6).unwrap();
+    /// let board = Board::decode(serialized, 6, 6).unwrap();
     /// let expected = vec![
     ///   Point::new(1, 2),
     ///   Point::new(2, 4),
@@ -377,11 +636,11 @@ impl Board {
     /// assert_eq!(board.placeable(Piece::Black), expected);
     /// ```
     pub fn placeable(&self, next: Piece) -> Vec<Point> {
-        // yes, this is O(n^2) in time, but does it really matter if the size of the board (=n) is less than 255
+        // yes, this is O(w*h) in time, but does it really matter if the size of the board is less than 255
         // and it's Rust (not something slow and memory intensive like JS or Python)?
         let mut ret = Vec::new();
-        for x in 0..self.size {
-            for y in 0..self.size {
+        for x in 0..self.width {
+            for y in 0..self.height {
                 let point = Point::new(x, y);
                 if self.count_flips(point, next) > 0 {
                     ret.push(point);
@@ -411,7 +670,7 @@ mod test_board {
             ww.bbw
             ww.bbw
         ";
-        let decoded = Board::decode(serialized, 6);
+        let decoded = Board::decode(serialized, 6, 6);
         assert!(decoded.is_ok(), "{:?}", decoded);
     }
     #[test]
@@ -432,8 +691,8 @@ mod test_board {
             ww.bbw
             ww.bbb
         ";
-        let board = Board::decode(input, 6).unwrap();
-        let expected = Board::decode(expected, 6).unwrap();
+        let board = Board::decode(input, 6, 6).unwrap();
+        let expected = Board::decode(expected, 6, 6).unwrap();
 
         let board = board.place(Point::new(2, 2), Piece::Black).unwrap();
         assert_eq!(board, expected);
@@ -456,8 +715,8 @@ mod test_board {
             ......
             ......
         ";
-        let board = Board::decode(input, 6).unwrap();
-        let expected = Board::decode(expected, 6).unwrap();
+        let board = Board::decode(input, 6, 6).unwrap();
+        let expected = Board::decode(expected, 6, 6).unwrap();
 
         let board = board.place(Point::new(0, 0), Piece::Black).unwrap();
         let board = board.place(Point::new(0, 1), Piece::Black).unwrap();
@@ -484,14 +743,130 @@ mod test_board {
             b.b.b.
             ..b..b
         ";
-        let board = Board::decode(input, 6).unwrap();
-        let expected = Board::decode(expected, 6).unwrap();
+        let board = Board::decode(input, 6, 6).unwrap();
+        let expected = Board::decode(expected, 6, 6).unwrap();
 
         let board = board.place(Point::new(2, 2), Piece::Black).unwrap();
 
         assert_eq!(board, expected);
     }
     #[test]
+    fn rectangular() {
+        let input = "
+            ........
+            ...bw...
+            ...wb...
+            ........
+        ";
+        let board = Board::decode(input, 8, 4).unwrap();
+        assert_eq!(board, Board::new(8, 4));
+        assert!(Board::decode(input, 4, 8).is_err());
+        let expected = vec![
+            Point::new(2, 2),
+            Point::new(3, 3),
+            Point::new(4, 0),
+            Point::new(5, 1),
+        ];
+        assert_eq!(board.placeable(Piece::Black), expected);
+        let board = board.place(Point::new(5, 1), Piece::Black).unwrap();
+        assert_eq!(board.score(Piece::Black), 4);
+    }
+    #[test]
+    fn blocked() {
+        let input = "
+            .w#wb.
+            ......
+            ..#...
+            .wwb..
+            ..b...
+            #.....
+        ";
+        let board = Board::decode(input, 6, 6).unwrap();
+        // lines are cut at blocked cells
+        assert_eq!(board.count_flips(Point::new(0, 0), Piece::Black), 0);
+        assert_eq!(board.count_flips(Point::new(0, 3), Piece::Black), 2);
+        assert!(!board.placeable(Piece::Black).contains(&Point::new(0, 0)));
+        // blocked cells can't be played on
+        assert_eq!(board.count_flips(Point::new(2, 2), Piece::Black), 0);
+        let err = board
+            .clone()
+            .place(Point::new(2, 2), Piece::Black)
+            .unwrap_err();
+        assert_eq!(err.kind, PlaceErrorKind::Blocked);
+        let err = board
+            .clone()
+            .place(Point::new(0, 5), Piece::White)
+            .unwrap_err();
+        assert_eq!(err.kind, PlaceErrorKind::Blocked);
+        // and they don't count for anyone
+        assert_eq!(board.score(Piece::Black), 3);
+        assert_eq!(board.score(Piece::White), 4);
+        let board = board.place(Point::new(0, 3), Piece::Black).unwrap();
+        assert_eq!(board.get(Point::new(2, 2)), Ok(Cell::Blocked));
+    }
+    #[test]
+    fn winner() {
+        let input = "
+            bbw.
+            www.
+            ....
+            ....
+        ";
+        let board = Board::decode(input, 4, 4).unwrap();
+        assert_eq!(board.winner(), Some(Piece::White));
+        let board = board.with_win_condition(WinCondition::FewestDiscs);
+        assert_eq!(board.winner(), Some(Piece::Black));
+        let board = board.place(Point::new(3, 0), Piece::Black).unwrap();
+        assert_eq!(board.winner(), Some(Piece::White));
+        assert!(!board.is_over());
+        let tie = Board::decode("bw\nwb", 2, 2).unwrap();
+        assert_eq!(tie.winner(), None);
+        assert!(tie.is_over());
+    }
+    #[test]
+    fn torus() {
+        let input = "
+            ...bww
+            ......
+            ......
+            ......
+            b.....
+            w.....
+        ";
+        let expected = "
+            b..bbb
+            ......
+            ......
+            ......
+            b.....
+            b.....
+        ";
+        let board = Board::decode(input, 6, 6).unwrap();
+        assert_eq!(board.count_flips(Point::new(0, 0), Piece::Black), 0);
+        let board = board.with_topology(Topology::Torus);
+        let expected = Board::decode(expected, 6, 6)
+            .unwrap()
+            .with_topology(Topology::Torus);
+        // captures across the left edge and the top edge
+        assert_eq!(board.count_flips(Point::new(0, 0), Piece::Black), 3);
+        let board = board.place(Point::new(0, 0), Piece::Black).unwrap();
+        assert_eq!(board, expected);
+    }
+    #[test]
+    fn torus_line_without_end() {
+        let input = "
+            .www
+            ....
+            ....
+            ....
+        ";
+        let board = Board::decode(input, 4, 4)
+            .unwrap()
+            .with_topology(Topology::Torus);
+        // the row comes back to where it started, so there's nothing to capture
+        assert_eq!(board.count_flips(Point::new(0, 0), Piece::Black), 0);
+    }
+    #[test]
     fn count_flip() {
         let table = "
             .bw.bb
@@ -501,7 +876,7 @@ mod test_board {
             ww.bbw
             ww.bbw
         ";
-        let table = Board::decode(table, 6).unwrap();
+        let table = Board::decode(table, 6, 6).unwrap();
         assert_eq!(table.count_flips(Point::new(0, 0), Piece::White), 3);
         assert_eq!(table.count_flips(Point::new(5, 3), Piece::White), 6);
         assert_eq!(table.count_flips(Point::new(5, 3), Piece::Black), 1);
@@ -514,6 +889,65 @@ mod test_board {
         assert_eq!(table.count_flips(Point::new(3, 3), Piece::White), 0);
         assert_eq!(table.count_flips(Point::new(2, 1), Piece::White), 0);
     }
+    #[test]
+    fn torus_lines_crossing() {
+        // the two diagonals from a1 meet at d4, which mustn't count as flipped for the other
+        let board = Board::decode(
+            "
+            ......
+            .w...w
+            ..w.w.
+            ...w..
+            ....b.
+            ......
+        ",
+            6,
+            6,
+        )
+        .unwrap()
+        .with_topology(Topology::Torus);
+        let at = Point::new(0, 0);
+        let flips = board.count_flips(at, Piece::Black);
+        let placed = board.clone().place(at, Piece::Black).unwrap();
+        assert_eq!(flips, 3);
+        assert_eq!(
+            placed.score(Piece::Black),
+            board.score(Piece::Black) + 1 + flips
+        );
+    }
+    #[test]
+    fn legal_mask() {
+        let board = Board::new(6, 6);
+        let mask = board.legal_mask(Piece::Black);
+        assert_eq!(mask.len(), 36);
+        for at in board.placeable(Piece::Black) {
+            assert_eq!(mask[at.y * 6 + at.x], 1);
+        }
+        assert_eq!(mask.iter().filter(|&&m| m == 1).count(), 4);
+    }
+    #[test]
+    fn errors() {
+        let board = Board::new(6, 6);
+        let occupied = Point::new(2, 2);
+        let error = OthelloError::from(board.clone().place(occupied, Piece::White).unwrap_err());
+        assert_eq!(error.kind(), OthelloErrorKind::AlreadyOccupied);
+        assert_eq!(error.point(), Some(occupied));
+        assert_eq!(error.player(), Some(Piece::White));
+        let error = OthelloError::from(board.place(Point::new(0, 0), Piece::Black).unwrap_err());
+        assert_eq!(error.kind(), OthelloErrorKind::NoPiecesChanged);
+
+        let error = OthelloError::from(Board::decode("bw", 2, 2).unwrap_err());
+        assert_eq!(error.kind(), OthelloErrorKind::InvalidBoard);
+        assert_eq!(error.point(), None);
+        assert_eq!(
+            Board::try_new(0, 8).unwrap_err().kind(),
+            OthelloErrorKind::InvalidSize
+        );
+        assert_eq!(
+            Board::try_new(256, 8).unwrap_err().kind(),
+            OthelloErrorKind::InvalidSize
+        );
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -525,11 +959,25 @@ pub struct PlaceError {
     kind: PlaceErrorKind,
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PlaceErrorKind {
+#[wasm_bindgen]
+pub enum PlaceErrorKind {
     AlreadyOccupied,
+    Blocked,
     OutOfBoundary,
     NoPiecesChanged,
 }
+#[wasm_bindgen]
+impl PlaceError {
+    pub fn kind(&self) -> PlaceErrorKind {
+        self.kind
+    }
+    pub fn at(&self) -> Point {
+        self.at
+    }
+    pub fn player(&self) -> Piece {
+        self.player
+    }
+}
 impl From<PlaceError> for String {
     fn from(error: PlaceError) -> String {
         let PlaceError {
@@ -544,6 +992,100 @@ impl From<PlaceError> for String {
     }
 }
 
+/// what went wrong, for JS. the first four are as in `PlaceErrorKind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[wasm_bindgen]
+pub enum OthelloErrorKind {
+    AlreadyOccupied,
+    Blocked,
+    OutOfBoundary,
+    NoPiecesChanged,
+    /// see `Board::try_new`.
+    InvalidSize,
+    /// a serialized board that `Board::decode` couldn't read.
+    InvalidBoard,
+    /// an `OthelloSession` was asked to move for the side that isn't to move.
+    NotYourTurn,
+    /// passing while there is a legal move.
+    CannotPass,
+    /// a move after neither side can place anymore.
+    GameOver,
+    /// `OthelloSession::ai_move` found nothing to play although the AI can place.
+    NoMove,
+}
+impl From<PlaceErrorKind> for OthelloErrorKind {
+    fn from(kind: PlaceErrorKind) -> Self {
+        match kind {
+            PlaceErrorKind::AlreadyOccupied => OthelloErrorKind::AlreadyOccupied,
+            PlaceErrorKind::Blocked => OthelloErrorKind::Blocked,
+            PlaceErrorKind::OutOfBoundary => OthelloErrorKind::OutOfBoundary,
+            PlaceErrorKind::NoPiecesChanged => OthelloErrorKind::NoPiecesChanged,
+        }
+    }
+}
+
+/// the error every othello function exported to JS throws, instead of a string or a crash.
+/// `point` and `player` are there when a move was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct OthelloError {
+    kind: OthelloErrorKind,
+    point: Option<Point>,
+    player: Option<Piece>,
+    message: String,
+}
+#[wasm_bindgen]
+impl OthelloError {
+    pub fn kind(&self) -> OthelloErrorKind {
+        self.kind
+    }
+    pub fn point(&self) -> Option<Point> {
+        self.point
+    }
+    pub fn player(&self) -> Option<Piece> {
+        self.player
+    }
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+}
+impl OthelloError {
+    /// an error that isn't about a particular move.
+    pub(crate) fn new(kind: OthelloErrorKind, message: &str) -> Self {
+        OthelloError {
+            kind,
+            point: None,
+            player: None,
+            message: message.to_string(),
+        }
+    }
+}
+impl From<PlaceError> for OthelloError {
+    fn from(error: PlaceError) -> Self {
+        OthelloError {
+            kind: error.kind.into(),
+            point: Some(error.at),
+            player: Some(error.player),
+            message: error.into(),
+        }
+    }
+}
+impl From<DecodeError> for OthelloError {
+    fn from(error: DecodeError) -> Self {
+        OthelloError {
+            kind: OthelloErrorKind::InvalidBoard,
+            point: None,
+            player: None,
+            message: error.into(),
+        }
+    }
+}
+impl From<OthelloError> for String {
+    fn from(error: OthelloError) -> String {
+        error.message
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DecodeError {
     UnknownChar(char),
@@ -558,19 +1100,24 @@ pub enum DecodeError {
     },
 }
 
-impl From<DecodeError> for JsValue {
+impl From<DecodeError> for String {
     fn from(error: DecodeError) -> Self {
         match error {
-            DecodeError::UnknownChar(c) => format!("Unknown character: {}", c).into(),
+            DecodeError::UnknownChar(c) => format!("Unknown character: {}", c),
             DecodeError::UnmatchedOverallLength { expected, got } => {
-                format!("Expected {} rows, got {}", expected, got).into()
+                format!("Expected {} rows, got {}", expected, got)
             }
             DecodeError::UnmatchedLocalLength { at, expected, got } => {
-                format!("Row {} expected {} columns, got {}", at, expected, got).into()
+                format!("Row {} expected {} columns, got {}", at, expected, got)
             }
         }
     }
 }
+impl From<DecodeError> for JsValue {
+    fn from(error: DecodeError) -> Self {
+        String::from(error).into()
+    }
+}
 
 // returns pieces that were flipped
 pub fn flip_in_direction(b: &mut Board, at: Point, piece: Piece, direction: Direction) -> usize {
@@ -578,7 +1125,7 @@ pub fn flip_in_direction(b: &mut Board, at: Point, piece: Piece, direction: Dire
         return 0;
     };
     let mut flipped = 0;
-    while let Ok(pos) = at.move_for(direction.times(flipped as isize + 1)) {
+    while let Ok(pos) = b.move_for(at, direction.times(flipped as isize + 1)) {
         let Ok(cell) = b.get(pos) else {
             break;
         };
@@ -599,9 +1146,12 @@ pub fn count_planned_flip_in_direction(
 ) -> usize {
     let mut flipping_pieces: usize = 0;
     loop {
-        let Ok(pos) = at.move_for(direction.times(flipping_pieces as isize + 1)) else {
+        let Ok(pos) = b.move_for(at, direction.times(flipping_pieces as isize + 1)) else {
             return 0;
         };
+        if pos == at {
+            return 0; // wrapped all the way around a torus without meeting our own piece
+        }
         let Ok(cell) = b.get(pos) else {
             return 0;
         };
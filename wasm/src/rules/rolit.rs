@@ -0,0 +1,518 @@
+//! Rolit: othello for 2 to 4 players.
+//!
+//! differences from othello:
+//! - every player has their own colour, and turns go Red -> Yellow -> Green -> Blue.
+//! - a placement captures lines of *any* other colour that end with the player's own disc.
+//! - if the player has no capturing placement, they may place on any empty cell adjacent to a disc.
+//!   so nobody ever has to pass, and the game ends when the board is full.
+use super::othello::{Direction, OutOfBoundaryError, Point, EIGHT_DIRECTIONS};
+use super::{GameError, GameErrorKind};
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[wasm_bindgen]
+pub enum Color {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+}
+impl Color {
+    pub const ALL: [Color; 4] = [Color::Red, Color::Yellow, Color::Green, Color::Blue];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Color::Red => 0,
+            Color::Yellow => 1,
+            Color::Green => 2,
+            Color::Blue => 3,
+        }
+    }
+    /// the player who plays after this one, in a game of `players` players.
+    /// `players` is taken as 2 to 4, so that a stray count never goes past `Color::ALL`.
+    pub fn next(&self, players: usize) -> Color {
+        Color::ALL[(self.index() + 1) % players.clamp(2, 4)]
+    }
+    fn to_char(self) -> char {
+        match self {
+            Color::Red => 'r',
+            Color::Yellow => 'y',
+            Color::Green => 'g',
+            Color::Blue => 'b',
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+#[wasm_bindgen]
+pub struct RolitBoard {
+    pub width: usize,
+    pub height: usize,
+    // private, as `colors` slices `Color::ALL` with it. JS reads it through `players()`.
+    players: usize,
+    data: Vec<Vec<Option<Color>>>,
+}
+
+#[wasm_bindgen]
+impl RolitBoard {
+    /// number of players, 2 to 4. players are the first `players` colours of `Color::ALL`.
+    #[wasm_bindgen(getter)]
+    pub fn players(&self) -> usize {
+        self.players
+    }
+    /// should return Array<Array<"." | "r" | "y" | "g" | "b">>
+    pub fn get_data(&self) -> wasm_bindgen::JsValue {
+        let data: String = self
+            .data
+            .iter()
+            .map(|row| {
+                format!(
+                    "[{}]",
+                    row.iter()
+                        .map(|cell| match cell {
+                            None => r#"".""#.to_string(),
+                            Some(color) => format!(r#""{}""#, color.to_char()),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(",")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        js_sys::JSON::parse(&format!("[{}]", data)).unwrap()
+    }
+
+    pub fn score(&self, player: Color) -> usize {
+        self.data
+            .iter()
+            .map(|row| row.iter().filter(|&&cell| cell == Some(player)).count())
+            .sum()
+    }
+
+    /// the game is over when the board is full.
+    pub fn is_over(&self) -> bool {
+        self.data.iter().flatten().all(|cell| cell.is_some())
+    }
+}
+
+impl RolitBoard {
+    /// creates a board with one disc of every colour at the centre, clockwise from top left.
+    /// ```rust
+    /// use boardgame_ai::rules::rolit::RolitBoard;
+    /// let expected = "
+    /// ......
+    /// ......
+    /// ..ry..
+    /// ..bg..
+    /// ......
+    /// ......
+    /// ";
+    /// assert_eq!(RolitBoard::new(6, 6, 4).encode(), expected);
+    /// ```
+    /// panics on what `try_new` rejects.
+    pub fn new(width: usize, height: usize, players: usize) -> Self {
+        Self::try_new(width, height, players).unwrap_or_else(|error| panic!("{}", error.message()))
+    }
+    /// the starting board. 2 to 4 players, and width and height even and between 2 and 254.
+    pub fn try_new(width: usize, height: usize, players: usize) -> Result<Self, GameError> {
+        if !(2..=4).contains(&players) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidPlayers,
+                "rolit is played by 2 to 4 players",
+            ));
+        }
+        if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "width and height must be divisible by 2",
+            ));
+        }
+        if !(2..255).contains(&width) || !(2..255).contains(&height) {
+            return Err(GameError::new(
+                GameErrorKind::InvalidSize,
+                "width and height should be between 2 and 254",
+            ));
+        }
+        let mut new = Self {
+            width,
+            height,
+            players,
+            data: vec![vec![None; width]; height],
+        };
+        let (cx, cy) = (width / 2, height / 2);
+        new.data[cy - 1][cx - 1] = Some(Color::Red);
+        new.data[cy - 1][cx] = Some(Color::Yellow);
+        new.data[cy][cx] = Some(Color::Green);
+        new.data[cy][cx - 1] = Some(Color::Blue);
+        Ok(new)
+    }
+    pub fn get(&self, at: Point) -> Result<Option<Color>, OutOfBoundaryError> {
+        Ok(*self
+            .data
+            .get(at.y)
+            .ok_or(OutOfBoundaryError())?
+            .get(at.x)
+            .ok_or(OutOfBoundaryError())?)
+    }
+    /// primitive operation. doesn't do anything other than setting the cell.
+    pub fn set(&mut self, at: Point, cell: Option<Color>) -> Result<(), OutOfBoundaryError> {
+        let slot = self
+            .data
+            .get_mut(at.y)
+            .ok_or(OutOfBoundaryError())?
+            .get_mut(at.x)
+            .ok_or(OutOfBoundaryError())?;
+        *slot = cell;
+        Ok(())
+    }
+    /// the colours that take part in this game, in turn order.
+    pub fn colors(&self) -> &'static [Color] {
+        &Color::ALL[..self.players]
+    }
+
+    /// count of discs that placing `player` at `at` would capture.
+    pub fn count_flips(&self, at: Point, player: Color) -> usize {
+        if self.get(at) != Ok(None) {
+            return 0;
+        }
+        EIGHT_DIRECTIONS
+            .iter()
+            .map(|&(x, y)| self.count_flips_in_direction(at, player, Direction::new(x, y)))
+            .sum()
+    }
+    fn count_flips_in_direction(&self, at: Point, player: Color, direction: Direction) -> usize {
+        let mut flipping = 0;
+        loop {
+            let Ok(pos) = at.move_for(direction.times(flipping as isize + 1)) else {
+                return 0;
+            };
+            match self.get(pos) {
+                Ok(Some(color)) if color == player => return flipping,
+                Ok(Some(_)) => flipping += 1,
+                _ => return 0,
+            }
+        }
+    }
+    fn is_adjacent_to_disc(&self, at: Point) -> bool {
+        EIGHT_DIRECTIONS.iter().any(|&(x, y)| {
+            at.move_for(Direction::new(x, y))
+                .ok()
+                .and_then(|pos| self.get(pos).ok())
+                .is_some_and(|cell| cell.is_some())
+        })
+    }
+    fn empty_points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| Point::new(x, y)))
+            .filter(move |&point| self.get(point) == Ok(None))
+    }
+
+    /// every legal placement for `player`.
+    /// capturing placements are mandatory, so if there are any, only they are returned.
+    /// ```rust
+    /// use boardgame_ai::rules::rolit::*;
+    /// use boardgame_ai::rules::othello::Point;
+    /// let board = RolitBoard::decode("
+    /// ....
+    /// .ry.
+    /// .bg.
+    /// ....
+    /// ", 4, 4, 4).unwrap();
+    /// assert_eq!(
+    ///     board.placeable(Color::Red),
+    ///     vec![Point::new(3, 1), Point::new(1, 3), Point::new(3, 3)],
+    /// );
+    /// ```
+    pub fn placeable(&self, player: Color) -> Vec<Point> {
+        let capturing: Vec<_> = self
+            .empty_points()
+            .filter(|&point| self.count_flips(point, player) > 0)
+            .collect();
+        if !capturing.is_empty() {
+            return capturing;
+        }
+        self.empty_points()
+            .filter(|&point| self.is_adjacent_to_disc(point))
+            .collect()
+    }
+
+    /// places `player`'s disc and flips the captured ones.
+    pub fn place(mut self, at: Point, player: Color) -> Result<RolitBoard, PlaceError> {
+        if !self.colors().contains(&player) {
+            return Err(PlaceError::NotPlaying);
+        }
+        match self.get(at) {
+            Err(_) => return Err(PlaceError::OutOfBoundary),
+            Ok(Some(_)) => return Err(PlaceError::AlreadyOccupied),
+            Ok(None) => {}
+        }
+        let flips: Vec<_> = EIGHT_DIRECTIONS
+            .iter()
+            .map(|&(x, y)| Direction::new(x, y))
+            .map(|dir| (dir, self.count_flips_in_direction(at, player, dir)))
+            .collect();
+        if flips.iter().all(|(_, count)| *count == 0) {
+            if !self.is_adjacent_to_disc(at) {
+                return Err(PlaceError::NotAdjacent);
+            }
+            if self
+                .empty_points()
+                .any(|point| self.count_flips(point, player) > 0)
+            {
+                return Err(PlaceError::MustCapture);
+            }
+        }
+        self.set(at, Some(player)).unwrap(); // already checked above
+        for (dir, count) in flips {
+            for i in 1..=count {
+                let pos = at.move_for(dir.times(i as isize)).unwrap(); // counted before, so it's on the board
+                self.set(pos, Some(player)).unwrap();
+            }
+        }
+        Ok(self)
+    }
+
+    /// the players with the most discs. more than one if it's a tie.
+    pub fn leaders(&self) -> Vec<Color> {
+        let best = self
+            .colors()
+            .iter()
+            .map(|&color| self.score(color))
+            .max()
+            .unwrap_or(0);
+        self.colors()
+            .iter()
+            .copied()
+            .filter(|&color| self.score(color) == best)
+            .collect()
+    }
+
+    /// same format as `othello::Board::decode`, with `r`, `y`, `g` and `b` (blue) as discs.
+    pub fn decode(
+        serialized: &str,
+        width: usize,
+        height: usize,
+        players: usize,
+    ) -> Result<RolitBoard, DecodeError> {
+        if !(2..=4).contains(&players) {
+            return Err(DecodeError::InvalidPlayers(players));
+        }
+        let rows: Vec<_> = serialized
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if rows.len() != height {
+            return Err(BoardDecodeError::UnmatchedOverallLength {
+                expected: height,
+                got: rows.len(),
+            }
+            .into());
+        }
+        let data = rows
+            .into_iter()
+            .enumerate()
+            .map(|(at, row)| {
+                let row = row
+                    .chars()
+                    .map(|char| match char {
+                        '.' | '_' => Ok(None),
+                        'r' => Ok(Some(Color::Red)),
+                        'y' => Ok(Some(Color::Yellow)),
+                        'g' => Ok(Some(Color::Green)),
+                        'b' => Ok(Some(Color::Blue)),
+                        _ => Err(BoardDecodeError::UnknownChar(char)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if row.len() == width {
+                    Ok(row)
+                } else {
+                    Err(BoardDecodeError::UnmatchedLocalLength {
+                        at,
+                        expected: width,
+                        got: row.len(),
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RolitBoard {
+            width,
+            height,
+            players,
+            data,
+        })
+    }
+    pub fn encode(&self) -> String {
+        "\n".to_string()
+            + &self
+                .data
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| cell.map_or('.', |color| color.to_char()))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+            + "\n"
+    }
+}
+impl fmt::Debug for RolitBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+use super::othello::DecodeError as BoardDecodeError;
+
+/// why `RolitBoard::decode` failed: the board itself, or a player count outside 2..=4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    Board(BoardDecodeError),
+    InvalidPlayers(usize),
+}
+impl From<BoardDecodeError> for DecodeError {
+    fn from(error: BoardDecodeError) -> Self {
+        DecodeError::Board(error)
+    }
+}
+impl From<DecodeError> for String {
+    fn from(error: DecodeError) -> Self {
+        match error {
+            DecodeError::Board(error) => error.into(),
+            DecodeError::InvalidPlayers(players) => format!("Can't be played by {}", players),
+        }
+    }
+}
+impl From<DecodeError> for JsValue {
+    fn from(error: DecodeError) -> Self {
+        String::from(error).into()
+    }
+}
+impl From<DecodeError> for GameError {
+    fn from(error: DecodeError) -> Self {
+        let kind = match error {
+            DecodeError::Board(_) => GameErrorKind::InvalidBoard,
+            DecodeError::InvalidPlayers(_) => GameErrorKind::InvalidPlayers,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceError {
+    OutOfBoundary,
+    AlreadyOccupied,
+    /// non-capturing placements must touch a disc.
+    NotAdjacent,
+    /// the player could capture somewhere, so they must.
+    MustCapture,
+    /// the colour isn't in this game.
+    NotPlaying,
+}
+impl From<PlaceError> for String {
+    fn from(error: PlaceError) -> String {
+        format!("Failed to place piece at board. error: {error:?}")
+    }
+}
+impl From<PlaceError> for GameError {
+    fn from(error: PlaceError) -> Self {
+        let kind = match error {
+            PlaceError::OutOfBoundary => GameErrorKind::OutOfBoundary,
+            PlaceError::AlreadyOccupied => GameErrorKind::AlreadyOccupied,
+            PlaceError::NotAdjacent => GameErrorKind::NotAdjacent,
+            PlaceError::MustCapture => GameErrorKind::MustCapture,
+            PlaceError::NotPlaying => GameErrorKind::NotPlaying,
+        };
+        GameError::new(kind, &String::from(error))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[test]
+    fn captures_any_other_colour() {
+        let board = "
+            bryg..
+            ......
+            ......
+            ......
+        ";
+        let expected = "
+            bbbbb.
+            ......
+            ......
+            ......
+        ";
+        let board = RolitBoard::decode(board, 6, 4, 4).unwrap();
+        let expected = RolitBoard::decode(expected, 6, 4, 4).unwrap();
+        assert_eq!(board.placeable(Color::Blue), vec![Point::new(4, 0)]);
+        let board = board.place(Point::new(4, 0), Color::Blue).unwrap();
+        assert_eq!(board, expected);
+    }
+    #[test]
+    fn placement_without_capture() {
+        let board = "
+            ......
+            .ry...
+            ......
+            ......
+        ";
+        let board = RolitBoard::decode(board, 6, 4, 3).unwrap();
+        // green can't capture, so it can place anywhere next to a disc
+        assert_eq!(board.placeable(Color::Green).len(), 10);
+        assert_eq!(
+            board.clone().place(Point::new(5, 3), Color::Green),
+            Err(PlaceError::NotAdjacent)
+        );
+        let board = board.place(Point::new(3, 2), Color::Green).unwrap();
+        // red can capture the yellow disc, so it must
+        assert_eq!(board.placeable(Color::Red), vec![Point::new(3, 1)]);
+        assert_eq!(
+            board.clone().place(Point::new(0, 0), Color::Red),
+            Err(PlaceError::MustCapture)
+        );
+        // blue isn't in a 3 player game
+        assert_eq!(
+            board.clone().place(Point::new(4, 1), Color::Blue),
+            Err(PlaceError::NotPlaying)
+        );
+        let board = board.place(Point::new(3, 1), Color::Red).unwrap();
+        assert_eq!(board.score(Color::Red), 3);
+        assert_eq!(board.leaders(), vec![Color::Red]);
+    }
+    #[test]
+    fn turn_order() {
+        assert_eq!(Color::Green.next(3), Color::Red);
+        assert_eq!(Color::Green.next(4), Color::Blue);
+        assert_eq!(Color::Blue.next(4), Color::Red);
+        assert_eq!(Color::Red.next(0), Color::Yellow);
+    }
+    #[test]
+    fn players_are_checked() {
+        let board = "
+            ....
+            .ry.
+            .bg.
+            ....
+        ";
+        assert_eq!(
+            RolitBoard::decode(board, 4, 4, 0),
+            Err(DecodeError::InvalidPlayers(0))
+        );
+        assert_eq!(
+            RolitBoard::decode(board, 4, 4, 5),
+            Err(DecodeError::InvalidPlayers(5))
+        );
+        assert!(RolitBoard::decode(board, 4, 4, 4).is_ok());
+        assert_eq!(
+            RolitBoard::decode("x", 1, 1, 2),
+            Err(DecodeError::Board(BoardDecodeError::UnknownChar('x')))
+        );
+        let error: GameError = RolitBoard::decode(board, 4, 4, 5).unwrap_err().into();
+        assert_eq!(error.kind(), GameErrorKind::InvalidPlayers);
+        assert_eq!(RolitBoard::new(6, 6, 3).players(), 3);
+    }
+}
@@ -0,0 +1,477 @@
+//! a game of othello against the AI, for pages that would otherwise keep the game loop themselves.
+//!
+//! `OthelloSession` owns the board, whose turn it is, the history for `undo`, both clocks and the AI.
+//! the page calls `play` when the human clicks, `think` / `ai_move` on the AI's turn and `pass` when
+//! `state().must_pass` says the side to move can't place, and reads what happened from `take_events`:
+//!
+//! ```js
+//! const session = OthelloSession.new(SessionConfig.new());
+//! session.play(Point.create(4, 5));
+//! while (!session.think(2000)?.finished) {} // or a slice per animation frame
+//! session.ai_move();
+//! JSON.parse(session.take_events()); // [{"type":"play","player":"b","at":"e6","flips":1}, ...]
+//! ```
+//!
+//! events are
+//!
+//! ```text
+//! {"type":"play","player":"b","at":"f5","flips":1}
+//! {"type":"pass","player":"w"}
+//! {"type":"undo","moves":2}
+//! {"type":"over","winner":"b","black":40,"white":24}
+//! ```
+//!
+//! where `winner` is null for a tie.
+use crate::ai::othello::{SearchProgress, Searcher};
+use crate::json::Json;
+use crate::rules::gravity_othello;
+use crate::rules::othello::{
+    Board, OthelloError, OthelloErrorKind, Piece, Point, Topology, WinCondition,
+};
+use crate::utils::now_ms;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionConfig {
+    pub width: usize,
+    pub height: usize,
+    /// 1..=10, as in `generate_ai_play`.
+    pub strength: usize,
+    pub human: Piece,
+    pub win_condition: WinCondition,
+    pub topology: Topology,
+    /// plays gravity othello (see `rules::gravity_othello`) instead.
+    pub gravity: bool,
+}
+#[wasm_bindgen]
+impl SessionConfig {
+    /// 8x8, strength 5, the human plays black.
+    pub fn new() -> SessionConfig {
+        SessionConfig::default()
+    }
+}
+impl Default for SessionConfig {
+    fn default() -> Self {
+        SessionConfig {
+            width: 8,
+            height: 8,
+            strength: 5,
+            human: Piece::Black,
+            win_condition: WinCondition::MostDiscs,
+            topology: Topology::Flat,
+            gravity: false,
+        }
+    }
+}
+
+/// a summary of the game, for rendering.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionState {
+    pub turn: Piece,
+    pub human: Piece,
+    pub black: usize,
+    pub white: usize,
+    /// time each side has spent on its moves, the side to move's current think included.
+    pub black_ms: f64,
+    pub white_ms: f64,
+    /// the side to move can't place, and has to `pass`.
+    pub must_pass: bool,
+    pub over: bool,
+    /// as in `Board::winner`, so it's also set while the game goes on.
+    pub winner: Option<Piece>,
+    pub can_undo: bool,
+    /// moves and passes played so far.
+    pub moves: usize,
+}
+
+// a position to go back to
+#[derive(Debug, Clone)]
+struct Snapshot {
+    board: Board,
+    turn: Piece,
+    clocks: [f64; 2],
+}
+
+#[wasm_bindgen]
+pub struct OthelloSession {
+    config: SessionConfig,
+    board: Board,
+    turn: Piece,
+    // black's then white's, in ms
+    clocks: [f64; 2],
+    turn_started: f64,
+    history: Vec<Snapshot>,
+    // the AI's search on the current position, kept across `think`s
+    searcher: Option<Searcher>,
+    events: Vec<Json>,
+}
+
+fn side(piece: Piece) -> &'static str {
+    match piece {
+        Piece::Black => "b",
+        Piece::White => "w",
+    }
+}
+
+fn clock(piece: Piece) -> usize {
+    match piece {
+        Piece::Black => 0,
+        Piece::White => 1,
+    }
+}
+
+#[wasm_bindgen]
+impl OthelloSession {
+    /// fails with `InvalidSize` for sizes `Board::try_new` rejects.
+    pub fn new(config: &SessionConfig) -> Result<OthelloSession, OthelloError> {
+        let board = if config.gravity {
+            gravity_othello::try_new(config.width, config.height)?
+        } else {
+            Board::try_new(config.width, config.height)?
+        };
+        Ok(OthelloSession {
+            config: *config,
+            board: board
+                .with_win_condition(config.win_condition)
+                .with_topology(config.topology),
+            turn: Piece::Black,
+            clocks: [0.0; 2],
+            turn_started: now_ms(),
+            history: Vec::new(),
+            searcher: None,
+            events: Vec::new(),
+        })
+    }
+
+    pub fn config(&self) -> SessionConfig {
+        self.config
+    }
+
+    /// the AI's strength from its next search on.
+    pub fn set_strength(&mut self, strength: usize) {
+        if strength != self.config.strength {
+            self.config.strength = strength;
+            self.searcher = None;
+        }
+    }
+
+    pub fn board(&self) -> Board {
+        self.board.clone()
+    }
+
+    /// the board before the last move or pass, e.g. for `Board::flip_mask`.
+    pub fn previous(&self) -> Option<Board> {
+        self.history.last().map(|snapshot| snapshot.board.clone())
+    }
+
+    pub fn state(&self) -> SessionState {
+        let mut clocks = self.clocks;
+        if !self.is_over() {
+            clocks[clock(self.turn)] += now_ms() - self.turn_started;
+        }
+        SessionState {
+            turn: self.turn,
+            human: self.config.human,
+            black: self.board.score(Piece::Black),
+            white: self.board.score(Piece::White),
+            black_ms: clocks[0],
+            white_ms: clocks[1],
+            must_pass: !self.is_over() && !self.can_move(self.turn),
+            over: self.is_over(),
+            winner: self.board.winner(),
+            can_undo: self.can_undo(),
+            moves: self.history.len(),
+        }
+    }
+
+    /// 1 where the side to move can click, laid out like `Board::cell_bytes`.
+    pub fn legal_mask(&self) -> Vec<u8> {
+        if self.config.gravity {
+            gravity_othello::legal_mask(&self.board, self.turn)
+        } else {
+            self.board.legal_mask(self.turn)
+        }
+    }
+
+    /// the human's move. in gravity othello only `at.x` matters, as the disc drops down that column.
+    pub fn play(&mut self, at: &Point) -> Result<(), OthelloError> {
+        self.check_turn(self.config.human)?;
+        self.place(*at)
+    }
+
+    /// searches about `node_budget` more positions for the AI's move, so the page can show progress.
+    /// None when it isn't the AI's turn, and in gravity othello, whose AI is quick enough to just `ai_move`.
+    pub fn think(&mut self, node_budget: usize) -> Option<SearchProgress> {
+        if self.config.gravity || self.turn == self.config.human || self.is_over() {
+            return None;
+        }
+        let (board, turn, strength) = (&self.board, self.turn, self.config.strength);
+        let searcher = self
+            .searcher
+            .get_or_insert_with(|| Searcher::new(board, turn, strength));
+        Some(searcher.step(node_budget))
+    }
+
+    /// plays the AI's move, finishing what `think` started. passes if the AI can't place.
+    pub fn ai_move(&mut self) -> Result<(), OthelloError> {
+        self.check_turn(self.config.human.flip())?;
+        if !self.can_move(self.turn) {
+            return self.pass();
+        }
+        let at = if self.config.gravity {
+            let plies = crate::ai::gravity_othello::plies(self.config.strength);
+            let column = crate::ai::gravity_othello::predict(&self.board, self.turn, plies);
+            column.map(|column| Point::new(column, 0))
+        } else {
+            let (board, turn, strength) = (&self.board, self.turn, self.config.strength);
+            let searcher = self
+                .searcher
+                .get_or_insert_with(|| Searcher::new(board, turn, strength));
+            let mut progress = searcher.progress();
+            while !progress.finished {
+                progress = searcher.step(usize::MAX);
+            }
+            progress.best()
+        };
+        // can_move says there is a move, so the search has found one
+        let at = at.ok_or_else(|| OthelloError::new(OthelloErrorKind::NoMove, "no move found"))?;
+        self.place(at)
+    }
+
+    /// passes for the side to move, which is only allowed when it can't place.
+    pub fn pass(&mut self) -> Result<(), OthelloError> {
+        if self.is_over() {
+            return Err(OthelloError::new(
+                OthelloErrorKind::GameOver,
+                "the game is over",
+            ));
+        }
+        if self.can_move(self.turn) {
+            return Err(OthelloError::new(
+                OthelloErrorKind::CannotPass,
+                "can't pass while there is a legal move",
+            ));
+        }
+        let event = Json::Object(vec![
+            ("type".to_string(), "pass".into()),
+            ("player".to_string(), side(self.turn).into()),
+        ]);
+        self.advance(self.board.clone(), event);
+        Ok(())
+    }
+
+    /// takes back moves up to the human's last turn, the AI's replies included.
+    /// returns false if the human hasn't moved yet.
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        let mut moves = 0;
+        while let Some(snapshot) = self.history.pop() {
+            moves += 1;
+            self.board = snapshot.board;
+            self.turn = snapshot.turn;
+            self.clocks = snapshot.clocks;
+            if self.turn == self.config.human {
+                break;
+            }
+        }
+        self.turn_started = now_ms();
+        self.searcher = None;
+        self.events.push(Json::Object(vec![
+            ("type".to_string(), "undo".into()),
+            ("moves".to_string(), moves.into()),
+        ]));
+        true
+    }
+
+    /// what happened since the last call, as a JSON array. see the module doc for the events.
+    pub fn take_events(&mut self) -> String {
+        Json::Array(std::mem::take(&mut self.events)).to_string()
+    }
+}
+
+impl OthelloSession {
+    fn is_over(&self) -> bool {
+        if self.config.gravity {
+            gravity_othello::is_over(&self.board)
+        } else {
+            self.board.is_over()
+        }
+    }
+    fn can_move(&self, piece: Piece) -> bool {
+        if self.config.gravity {
+            !gravity_othello::placeable(&self.board, piece).is_empty()
+        } else {
+            !self.board.placeable(piece).is_empty()
+        }
+    }
+    fn can_undo(&self) -> bool {
+        self.history
+            .iter()
+            .any(|snapshot| snapshot.turn == self.config.human)
+    }
+    fn check_turn(&self, piece: Piece) -> Result<(), OthelloError> {
+        if self.is_over() {
+            return Err(OthelloError::new(
+                OthelloErrorKind::GameOver,
+                "the game is over",
+            ));
+        }
+        if self.turn != piece {
+            return Err(OthelloError::new(
+                OthelloErrorKind::NotYourTurn,
+                &format!("it's {}'s turn", side(self.turn)),
+            ));
+        }
+        Ok(())
+    }
+    // places for the side to move
+    fn place(&mut self, at: Point) -> Result<(), OthelloError> {
+        let (board, at) = if self.config.gravity {
+            let board = gravity_othello::place(self.board.clone(), at.x, self.turn)?;
+            // place has succeeded, so the column had room
+            let landing = gravity_othello::landing(&self.board, at.x).unwrap_or(at);
+            (board, landing)
+        } else {
+            (self.board.clone().place(at, self.turn)?, at)
+        };
+        let flips = board
+            .flip_mask(&self.board)
+            .iter()
+            .filter(|&&changed| changed == 1)
+            .count();
+        let event = Json::Object(vec![
+            ("type".to_string(), "play".into()),
+            ("player".to_string(), side(self.turn).into()),
+            ("at".to_string(), at.notation().into()),
+            ("flips".to_string(), flips.into()),
+        ]);
+        self.advance(board, event);
+        Ok(())
+    }
+    // moves on to `board` and the other side's turn, keeping the current position for undo
+    fn advance(&mut self, board: Board, event: Json) {
+        let now = now_ms();
+        self.clocks[clock(self.turn)] += now - self.turn_started;
+        self.turn_started = now;
+        self.history.push(Snapshot {
+            board: std::mem::replace(&mut self.board, board),
+            turn: self.turn,
+            clocks: self.clocks,
+        });
+        self.turn = self.turn.flip();
+        self.searcher = None;
+        self.events.push(event);
+        if self.is_over() {
+            let (black, white) = (
+                self.board.score(Piece::Black),
+                self.board.score(Piece::White),
+            );
+            self.events.push(Json::Object(vec![
+                ("type".to_string(), "over".into()),
+                ("winner".to_string(), self.board.winner().map(side).into()),
+                ("black".to_string(), black.into()),
+                ("white".to_string(), white.into()),
+            ]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn session(config: SessionConfig) -> OthelloSession {
+        OthelloSession::new(&config).unwrap()
+    }
+    #[test]
+    fn plays_against_the_ai() {
+        let mut session = session(SessionConfig {
+            strength: 2,
+            ..SessionConfig::default()
+        });
+        let at = session.board().placeable(Piece::Black)[0];
+        assert_eq!(
+            session.ai_move().unwrap_err().kind(),
+            OthelloErrorKind::NotYourTurn
+        );
+        session.play(&at).unwrap();
+        assert_eq!(session.state().turn, Piece::White);
+        assert!(session.think(10).is_some());
+        session.ai_move().unwrap();
+        let state = session.state();
+        assert_eq!(
+            (state.turn, state.moves, state.black + state.white),
+            (Piece::Black, 2, 6)
+        );
+        let events = Json::parse(&session.take_events()).unwrap();
+        let events = events.as_array().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0].get("at").and_then(Json::as_str),
+            Some(at.notation().as_str())
+        );
+        assert_eq!(events[1].get("player").and_then(Json::as_str), Some("w"));
+        assert_eq!(session.take_events(), "[]");
+    }
+    #[test]
+    fn undo_goes_back_to_the_humans_turn() {
+        let mut session = session(SessionConfig {
+            strength: 1,
+            human: Piece::White,
+            ..SessionConfig::default()
+        });
+        assert!(!session.undo());
+        session.ai_move().unwrap();
+        let before = session.board();
+        assert!(!session.state().can_undo);
+        let at = before.placeable(Piece::White)[0];
+        session.play(&at).unwrap();
+        session.ai_move().unwrap();
+        assert!(session.undo());
+        assert_eq!(session.board(), before);
+        assert_eq!(session.state().turn, Piece::White);
+        assert_eq!(session.state().moves, 1);
+    }
+    #[test]
+    fn passes_and_game_over() {
+        let mut session = session(SessionConfig::default());
+        assert_eq!(
+            session.pass().unwrap_err().kind(),
+            OthelloErrorKind::CannotPass
+        );
+        // black can't place, white can: c2 takes b2
+        session.board = Board::decode("....\nwb..\n....\n....", 4, 4).unwrap();
+        assert!(session.state().must_pass);
+        session.pass().unwrap();
+        session.ai_move().unwrap();
+        let state = session.state();
+        assert!(state.over);
+        assert_eq!(state.winner, Some(Piece::White));
+        assert_eq!(
+            session.play(&Point::new(0, 0)).unwrap_err().kind(),
+            OthelloErrorKind::GameOver
+        );
+        let events = session.take_events();
+        assert!(events.ends_with(r#"{"type":"over","winner":"w","black":0,"white":3}]"#));
+    }
+    #[test]
+    fn gravity() {
+        let mut session = session(SessionConfig {
+            width: 6,
+            height: 6,
+            strength: 1,
+            gravity: true,
+            ..SessionConfig::default()
+        });
+        let column = gravity_othello::placeable(&session.board(), Piece::Black)[0];
+        // only the column matters
+        session.play(&Point::new(column, 0)).unwrap();
+        assert!(session.think(100).is_none());
+        session.ai_move().unwrap();
+        assert_eq!(session.state().moves, 2);
+        assert!(session.legal_mask().contains(&1));
+    }
+}
@@ -0,0 +1,529 @@
+//! round-robin and swiss tournaments between bots. the games themselves are played by `referee`.
+//!
+//! a `Tournament` is only the schedule and the results, so it can be saved after every game
+//! (`to_json`) and picked up again after an interruption (`from_json`).
+//!
+//! - round robin: everyone plays everyone once, scheduled up front with the circle method.
+//! - swiss: a fixed number of rounds, each paired once the one before it is over. players with the same
+//!   standing meet, avoiding rematches where possible. with an odd number of players,
+//!   the lowest ranked player that hasn't had one gets a bye, which counts as a win by 0 discs.
+//!
+//! a win is 1 point and a draw ½. ties in points are broken by disc differential, then by Buchholz
+//! (the sum of the opponents' points).
+use crate::json::Json;
+use crate::referee::Bot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    RoundRobin,
+    Swiss { rounds: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entrant {
+    pub name: String,
+    /// a command, or `ai:<strength>`. see `Bot::parse`.
+    pub bot: String,
+}
+impl Entrant {
+    pub fn bot(&self) -> Bot {
+        match Bot::parse(&self.bot) {
+            Bot::Command { command, .. } => Bot::Command {
+                name: self.name.clone(),
+                command,
+            },
+            ai => ai,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pairing {
+    /// from 1.
+    pub round: usize,
+    /// indices into `entrants`.
+    pub black: usize,
+    /// None is a bye for `black`.
+    pub white: Option<usize>,
+    /// black's and white's discs, once played.
+    pub result: Option<(usize, usize)>,
+}
+impl Pairing {
+    pub fn is_played(&self) -> bool {
+        self.white.is_none() || self.result.is_some()
+    }
+    /// `entrant`'s points, own discs and opponent's discs. None if `entrant` isn't in it or it's not played yet.
+    fn outcome_for(&self, entrant: usize) -> Option<(f64, usize, usize)> {
+        let Some(white) = self.white else {
+            return (self.black == entrant).then_some((1.0, 0, 0));
+        };
+        let (black_discs, white_discs) = self.result?;
+        let (own, other) = if self.black == entrant {
+            (black_discs, white_discs)
+        } else if white == entrant {
+            (white_discs, black_discs)
+        } else {
+            return None;
+        };
+        let points = match own.cmp(&other) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        };
+        Some((points, own, other))
+    }
+    fn opponent_of(&self, entrant: usize) -> Option<usize> {
+        match self.white {
+            Some(white) if self.black == entrant => Some(white),
+            Some(white) if white == entrant => Some(self.black),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub entrant: usize,
+    pub points: f64,
+    pub disc_differential: isize,
+    pub buchholz: f64,
+    pub games: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tournament {
+    pub format: Format,
+    /// of the board.
+    pub size: usize,
+    /// per move.
+    pub time_ms: usize,
+    pub entrants: Vec<Entrant>,
+    pub pairings: Vec<Pairing>,
+}
+
+impl Tournament {
+    pub fn new(format: Format, size: usize, time_ms: usize, entrants: Vec<Entrant>) -> Self {
+        let mut tournament = Tournament {
+            format,
+            size,
+            time_ms,
+            entrants,
+            pairings: Vec::new(),
+        };
+        if format == Format::RoundRobin {
+            tournament.pairings = round_robin(tournament.entrants.len());
+        }
+        tournament
+    }
+
+    pub fn rounds(&self) -> usize {
+        match self.format {
+            Format::RoundRobin => self.pairings.iter().map(|p| p.round).max().unwrap_or(0),
+            Format::Swiss { rounds } => rounds,
+        }
+    }
+
+    /// the index of the next game to play, pairing the next swiss round if the current one is over.
+    /// None once the tournament is over.
+    pub fn next_game(&mut self) -> Option<usize> {
+        let unplayed = |pairings: &[Pairing]| pairings.iter().position(|p| !p.is_played());
+        if let Some(index) = unplayed(&self.pairings) {
+            return Some(index);
+        }
+        let played_rounds = self.pairings.iter().map(|p| p.round).max().unwrap_or(0);
+        match self.format {
+            Format::Swiss { rounds } if played_rounds < rounds && self.entrants.len() >= 2 => {
+                let round = self.swiss_round(played_rounds + 1);
+                self.pairings.extend(round);
+                unplayed(&self.pairings)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn record(&mut self, game: usize, black_discs: usize, white_discs: usize) {
+        self.pairings[game].result = Some((black_discs, white_discs));
+    }
+
+    fn swiss_round(&self, round: usize) -> Vec<Pairing> {
+        let mut unpaired: Vec<usize> = self.standings().into_iter().map(|s| s.entrant).collect();
+        let mut pairings = Vec::new();
+        if unpaired.len() % 2 == 1 {
+            let had_bye = |entrant: usize| {
+                self.pairings
+                    .iter()
+                    .any(|p| p.black == entrant && p.white.is_none())
+            };
+            let index = unpaired
+                .iter()
+                .rposition(|&entrant| !had_bye(entrant))
+                .unwrap_or(unpaired.len() - 1);
+            let entrant = unpaired.remove(index);
+            pairings.push(Pairing {
+                round,
+                black: entrant,
+                white: None,
+                result: None,
+            });
+        }
+        let met = |a: usize, b: usize| self.pairings.iter().any(|p| p.opponent_of(a) == Some(b));
+        let blacks = |entrant: usize| {
+            let pairings = self.pairings.iter();
+            pairings
+                .filter(|p| p.black == entrant && p.white.is_some())
+                .count()
+        };
+        while !unpaired.is_empty() {
+            let first = unpaired.remove(0);
+            let index = unpaired
+                .iter()
+                .position(|&other| !met(first, other))
+                .unwrap_or(0); // everyone left has played `first` already
+            let second = unpaired.remove(index);
+            let (black, white) = if blacks(first) <= blacks(second) {
+                (first, second)
+            } else {
+                (second, first)
+            };
+            pairings.push(Pairing {
+                round,
+                black,
+                white: Some(white),
+                result: None,
+            });
+        }
+        pairings
+    }
+
+    /// best first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<_> = (0..self.entrants.len())
+            .map(|entrant| {
+                let outcomes = self.pairings.iter().filter_map(|p| p.outcome_for(entrant));
+                let (mut points, mut disc_differential, mut games) = (0.0, 0, 0);
+                for (gained, own, other) in outcomes {
+                    points += gained;
+                    disc_differential += own as isize - other as isize;
+                    games += 1;
+                }
+                Standing {
+                    entrant,
+                    points,
+                    disc_differential,
+                    buchholz: 0.0,
+                    games,
+                }
+            })
+            .collect();
+        let points: Vec<f64> = standings.iter().map(|s| s.points).collect();
+        for standing in standings.iter_mut() {
+            standing.buchholz = self
+                .pairings
+                .iter()
+                .filter(|p| p.is_played())
+                .filter_map(|p| p.opponent_of(standing.entrant))
+                .map(|opponent| points[opponent])
+                .sum();
+        }
+        standings.sort_by(|a, b| {
+            b.points
+                .total_cmp(&a.points)
+                .then(b.disc_differential.cmp(&a.disc_differential))
+                .then(b.buchholz.total_cmp(&a.buchholz))
+                .then(a.entrant.cmp(&b.entrant))
+        });
+        standings
+    }
+
+    pub fn to_json(&self) -> String {
+        let (format, rounds) = match self.format {
+            Format::RoundRobin => ("round-robin", None),
+            Format::Swiss { rounds } => ("swiss", Some(rounds)),
+        };
+        let entrants = self.entrants.iter().map(|entrant| {
+            Json::Object(vec![
+                ("name".to_string(), entrant.name.as_str().into()),
+                ("bot".to_string(), entrant.bot.as_str().into()),
+            ])
+        });
+        let pairings = self.pairings.iter().map(|pairing| {
+            let result = pairing
+                .result
+                .map_or(Json::Null, |(b, w)| Json::Array(vec![b.into(), w.into()]));
+            Json::Object(vec![
+                ("round".to_string(), pairing.round.into()),
+                ("black".to_string(), pairing.black.into()),
+                ("white".to_string(), pairing.white.into()),
+                ("result".to_string(), result),
+            ])
+        });
+        let json = Json::Object(vec![
+            ("format".to_string(), format.into()),
+            ("rounds".to_string(), rounds.into()),
+            ("size".to_string(), self.size.into()),
+            ("time_ms".to_string(), self.time_ms.into()),
+            ("entrants".to_string(), Json::Array(entrants.collect())),
+            ("pairings".to_string(), Json::Array(pairings.collect())),
+        ]);
+        json.to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Tournament, String> {
+        let json = Json::parse(text)?;
+        let field = |json: &Json, key: &str| -> Result<Json, String> {
+            json.get(key)
+                .cloned()
+                .ok_or_else(|| format!("missing field: {}", key))
+        };
+        let number = |json: &Json, key: &str| -> Result<usize, String> {
+            field(json, key)?
+                .as_usize()
+                .ok_or_else(|| format!("not a number: {}", key))
+        };
+        let format = match field(&json, "format")?.as_str() {
+            Some("round-robin") => Format::RoundRobin,
+            Some("swiss") => Format::Swiss {
+                rounds: number(&json, "rounds")?,
+            },
+            _ => return Err("unknown format".to_string()),
+        };
+        let entrants = field(&json, "entrants")?
+            .as_array()
+            .ok_or("entrants should be an array")?
+            .iter()
+            .map(|entrant| {
+                let text = |key| {
+                    field(entrant, key)?
+                        .as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| format!("not a string: {}", key))
+                };
+                Ok(Entrant {
+                    name: text("name")?,
+                    bot: text("bot")?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let pairings = field(&json, "pairings")?
+            .as_array()
+            .ok_or("pairings should be an array")?
+            .iter()
+            .map(|pairing| {
+                let entrant = |json: &Json| {
+                    json.as_usize()
+                        .filter(|&i| i < entrants.len())
+                        .ok_or_else(|| "unknown entrant".to_string())
+                };
+                let white = match field(pairing, "white")? {
+                    Json::Null => None,
+                    white => Some(entrant(&white)?),
+                };
+                let result = match field(pairing, "result")? {
+                    Json::Array(discs) => match discs.as_slice() {
+                        [black, white] => black.as_usize().zip(white.as_usize()),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                Ok(Pairing {
+                    round: number(pairing, "round")?,
+                    black: entrant(&field(pairing, "black")?)?,
+                    white,
+                    result,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Tournament {
+            format,
+            size: number(&json, "size")?,
+            time_ms: number(&json, "time_ms")?,
+            entrants,
+            pairings,
+        })
+    }
+
+    /// the standings as an HTML page, with everyone's results against everyone else.
+    pub fn crosstable_html(&self) -> String {
+        let standings = self.standings();
+        let mut html = String::from(concat!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>crosstable</title>\n",
+            "<style>table { border-collapse: collapse; } th, td { border: 1px solid #888; padding: 2px 6px; text-align: center; }</style>\n",
+            "</head>\n<body>\n<table>\n<tr><th>#</th><th>name</th>"
+        ));
+        for rank in 1..=standings.len() {
+            html += &format!("<th>{}</th>", rank);
+        }
+        html += "<th>points</th><th>discs</th><th>buchholz</th></tr>\n";
+        for (rank, standing) in standings.iter().enumerate() {
+            let name = &self.entrants[standing.entrant].name;
+            html += &format!("<tr><td>{}</td><td>{}</td>", rank + 1, escape(name));
+            for other in standings.iter() {
+                if other.entrant == standing.entrant {
+                    html += "<td>×</td>";
+                    continue;
+                }
+                let games: Vec<_> = self
+                    .pairings
+                    .iter()
+                    .filter(|p| p.opponent_of(standing.entrant) == Some(other.entrant))
+                    .filter_map(|p| p.outcome_for(standing.entrant))
+                    .map(|(points, own, theirs)| {
+                        let symbol = match points {
+                            p if p >= 1.0 => "1",
+                            p if p > 0.0 => "½",
+                            _ => "0",
+                        };
+                        format!("<span title=\"{}-{}\">{}</span>", own, theirs, symbol)
+                    })
+                    .collect();
+                html += &format!("<td>{}</td>", games.join(" "));
+            }
+            html += &format!(
+                "<td>{}</td><td>{:+}</td><td>{}</td></tr>\n",
+                standing.points, standing.disc_differential, standing.buchholz
+            );
+        }
+        html += "</table>\n</body>\n</html>\n";
+        html
+    }
+}
+
+// pairings for everyone playing everyone once, by the circle method. colours alternate round by round.
+fn round_robin(entrants: usize) -> Vec<Pairing> {
+    // None sits out
+    let mut circle: Vec<Option<usize>> = (0..entrants).map(Some).collect();
+    if entrants % 2 == 1 {
+        circle.push(None);
+    }
+    let len = circle.len();
+    let mut pairings = Vec::new();
+    for round in 1..len {
+        for i in 0..len / 2 {
+            let (a, b) = (circle[i], circle[len - 1 - i]);
+            let (black, white) = if (round + i) % 2 == 0 { (a, b) } else { (b, a) };
+            let (black, white) = match (black, white) {
+                (Some(black), white) => (black, white),
+                (None, Some(white)) => (white, None),
+                (None, None) => continue,
+            };
+            let pairing = Pairing {
+                round,
+                black,
+                white,
+                result: None,
+            };
+            pairings.push(pairing);
+        }
+        // everyone but the first moves one seat along
+        circle[1..].rotate_right(1);
+    }
+    pairings
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn entrants(count: usize) -> Vec<Entrant> {
+        (0..count)
+            .map(|i| Entrant {
+                name: format!("bot {}", i),
+                bot: "ai:1".to_string(),
+            })
+            .collect()
+    }
+    // plays every game so that the lower index always wins by `index difference` discs
+    fn play_out(tournament: &mut Tournament) {
+        while let Some(game) = tournament.next_game() {
+            let pairing = tournament.pairings[game];
+            let white = pairing.white.unwrap();
+            let lead = pairing.black.abs_diff(white);
+            if pairing.black < white {
+                tournament.record(game, 32 + lead, 32 - lead);
+            } else {
+                tournament.record(game, 32 - lead, 32 + lead);
+            }
+        }
+    }
+    #[test]
+    fn round_robin_meets_everyone_once() {
+        for count in [4, 5].iter().copied() {
+            let mut tournament = Tournament::new(Format::RoundRobin, 8, 100, entrants(count));
+            assert_eq!(tournament.rounds(), if count == 4 { 3 } else { 5 });
+            for a in 0..count {
+                for b in a + 1..count {
+                    let games = tournament
+                        .pairings
+                        .iter()
+                        .filter(|p| p.opponent_of(a) == Some(b));
+                    assert_eq!(games.count(), 1, "{} vs {}", a, b);
+                }
+            }
+            play_out(&mut tournament);
+            let order: Vec<_> = tournament.standings().iter().map(|s| s.entrant).collect();
+            assert_eq!(order, (0..count).collect::<Vec<_>>());
+        }
+    }
+    #[test]
+    fn swiss_avoids_rematches() {
+        let mut tournament = Tournament::new(Format::Swiss { rounds: 3 }, 8, 100, entrants(4));
+        play_out(&mut tournament);
+        assert_eq!(tournament.pairings.len(), 6);
+        for a in 0..4 {
+            for b in a + 1..4 {
+                let games = tournament
+                    .pairings
+                    .iter()
+                    .filter(|p| p.opponent_of(a) == Some(b));
+                assert_eq!(games.count(), 1, "{} vs {}", a, b);
+            }
+        }
+        // odd: one bye per round, never twice to the same player
+        let mut tournament = Tournament::new(Format::Swiss { rounds: 3 }, 8, 100, entrants(5));
+        play_out(&mut tournament);
+        let mut byes: Vec<_> = tournament
+            .pairings
+            .iter()
+            .filter(|p| p.white.is_none())
+            .map(|p| p.black)
+            .collect();
+        assert_eq!(byes.len(), 3);
+        byes.dedup();
+        assert_eq!(byes.len(), 3);
+    }
+    #[test]
+    fn tie_breaks() {
+        let mut tournament = Tournament::new(Format::Swiss { rounds: 1 }, 8, 100, entrants(4));
+        tournament.next_game();
+        // both winners win, by different margins
+        tournament.record(0, 40, 24);
+        tournament.record(1, 33, 31);
+        let standings = tournament.standings();
+        let (winner, runner_up) = (&standings[0], &standings[1]);
+        assert_eq!((winner.points, runner_up.points), (1.0, 1.0));
+        assert_eq!(winner.disc_differential, 16);
+        assert_eq!(runner_up.disc_differential, 2);
+        assert_eq!(winner.buchholz, 0.0);
+    }
+    #[test]
+    fn json_round_trip() {
+        let mut tournament = Tournament::new(Format::Swiss { rounds: 2 }, 6, 500, entrants(3));
+        tournament.entrants[0].name = "quote \" and <tag>".to_string();
+        let game = tournament.next_game().unwrap();
+        let pairing = tournament.pairings[game];
+        if pairing.white.is_some() {
+            tournament.record(game, 20, 16);
+        }
+        let json = tournament.to_json();
+        assert_eq!(Tournament::from_json(&json), Ok(tournament.clone()));
+        let html = tournament.crosstable_html();
+        assert!(html.contains("quote &quot; and &lt;tag&gt;"));
+        assert!(Tournament::from_json("{}").is_err());
+    }
+}
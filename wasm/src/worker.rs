@@ -0,0 +1,463 @@
+//! the othello AI for a Web Worker, so deep searches don't freeze the page.
+//!
+//! the page and the worker talk in JSON strings, which `postMessage` can carry as they are.
+//! the page sends
+//!
+//! ```text
+//! {"type":"search","id":1,"board":"....\n.bw.\n.wb.\n....","width":4,"height":4,"player":"b","strength":5,"time_ms":2000}
+//! {"type":"search","id":2,"board":"...","width":8,"height":8,"player":"w","strength":5,"win_condition":"fewest_discs","topology":"torus"}
+//! {"type":"cancel","id":1}
+//! ```
+//!
+//! where `board` is as in `Board::encode`, `player` is the side to search for, and `time_ms`,
+//! `win_condition` (`"most_discs"` or `"fewest_discs"`) and `topology` (`"flat"` or `"torus"`) are optional.
+//! the search deepens one ply at a time up to what `strength` would search (see `ai::othello::predict`),
+//! and stops early once `time_ms` is over (though not before the first ply is done). the worker answers with
+//!
+//! ```text
+//! {"type":"progress","id":1,"depth":2,"best":"c1","score":12,"nodes":40,"tt_hits":0,"elapsed_ms":1,"nps":40000,"pv":"c1 a1"}
+//! {"type":"done","id":1,"depth":3,"best":"c1"}
+//! {"type":"cancelled","id":1}
+//! {"type":"error","id":1,"message":"..."}
+//! ```
+//!
+//! where `best` is in standard notation, or null for a pass (or no move at all, if the game is over).
+//! a worker can't get messages while wasm is running, so `AiDispatcher` searches a slice of
+//! at most `NODES_PER_STEP` positions per `step`, ending it early when a depth is done,
+//! and the worker yields in between, which is also when a cancel gets through:
+//!
+//! ```js
+//! const dispatcher = AiDispatcher.new();
+//! const post = (responses) => JSON.parse(responses).forEach((response) => postMessage(response));
+//! const work = () => {
+//!   if (!dispatcher.is_busy()) return;
+//!   post(dispatcher.step());
+//!   setTimeout(work, 0);
+//! };
+//! onmessage = (event) => {
+//!   post(dispatcher.receive(event.data));
+//!   setTimeout(work, 0);
+//! };
+//! ```
+//!
+//! searches are answered in the order they come in.
+use crate::ai::othello::{self as ai, State};
+use crate::ai::search;
+use crate::json::Json;
+use crate::rules::othello::{Board, Piece, Point, Topology, WinCondition};
+use crate::utils::now_ms;
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+// how often a step checks whether it has finished a depth or run out of time
+const NODES_PER_CHECK: usize = 50;
+/// the most positions a `step` searches, so that a cancel doesn't wait for a whole deep iteration.
+/// about 10ms on an 8x8 board, as each position is evaluated in full.
+pub const NODES_PER_STEP: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    Search {
+        id: usize,
+        board: Board,
+        player: Piece,
+        /// 1..=10, as in `generate_ai_play`.
+        strength: usize,
+        time_ms: Option<usize>,
+    },
+    Cancel {
+        id: usize,
+    },
+}
+impl Request {
+    /// ```rust
+    /// use boardgame_ai::worker::Request;
+    /// let request = Request::parse(r#"{"type":"cancel","id":3}"#).unwrap();
+    /// assert_eq!(request, Request::Cancel { id: 3 });
+    /// ```
+    pub fn parse(message: &str) -> Result<Request, String> {
+        let json = Json::parse(message)?;
+        let number = |key: &str| {
+            json.get(key)
+                .and_then(Json::as_usize)
+                .ok_or_else(|| format!("missing or bad {}", key))
+        };
+        let id = number("id")?;
+        match json.get("type").and_then(Json::as_str) {
+            Some("search") => {
+                let board = json
+                    .get("board")
+                    .and_then(Json::as_str)
+                    .ok_or("missing or bad board")?;
+                let board = Board::decode(board, number("width")?, number("height")?)
+                    .map_err(|error| format!("bad board: {:?}", error))?;
+                let win_condition = match json.get("win_condition").and_then(Json::as_str) {
+                    None | Some("most_discs") => WinCondition::MostDiscs,
+                    Some("fewest_discs") => WinCondition::FewestDiscs,
+                    Some(_) => {
+                        return Err("win_condition should be \"most_discs\" or \"fewest_discs\""
+                            .to_string())
+                    }
+                };
+                let topology = match json.get("topology").and_then(Json::as_str) {
+                    None | Some("flat") => Topology::Flat,
+                    Some("torus") => Topology::Torus,
+                    Some(_) => return Err("topology should be \"flat\" or \"torus\"".to_string()),
+                };
+                let board = board
+                    .with_win_condition(win_condition)
+                    .with_topology(topology);
+                let player = match json.get("player").and_then(Json::as_str) {
+                    Some("b") => Piece::Black,
+                    Some("w") => Piece::White,
+                    _ => return Err("player should be \"b\" or \"w\"".to_string()),
+                };
+                let time_ms = match json.get("time_ms") {
+                    None | Some(Json::Null) => None,
+                    Some(_) => Some(number("time_ms")?),
+                };
+                Ok(Request::Search {
+                    id,
+                    board,
+                    player,
+                    strength: number("strength")?,
+                    time_ms,
+                })
+            }
+            Some("cancel") => Ok(Request::Cancel { id }),
+            _ => Err("type should be \"search\" or \"cancel\"".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    /// the best move so far, searched `depth` plies ahead, and the rest of `ai::othello::Report`.
+    Progress {
+        id: usize,
+        depth: usize,
+        best: Option<Point>,
+        score: isize,
+        nodes: usize,
+        tt_hits: usize,
+        elapsed_ms: f64,
+        nps: f64,
+        /// as in `Report::pv_notation`.
+        pv: String,
+    },
+    /// the move to play. None if there is none, and the AI has to pass.
+    Done {
+        id: usize,
+        depth: usize,
+        best: Option<Point>,
+    },
+    Cancelled {
+        id: usize,
+    },
+    /// id is None if the request was too broken to tell.
+    Error {
+        id: Option<usize>,
+        message: String,
+    },
+}
+impl Response {
+    pub fn to_json(&self) -> Json {
+        let notation = |best: &Option<Point>| best.map(|at| at.notation()).into();
+        let entries: Vec<(&str, Json)> = match self {
+            Response::Progress {
+                id,
+                depth,
+                best,
+                score,
+                nodes,
+                tt_hits,
+                elapsed_ms,
+                nps,
+                pv,
+            } => vec![
+                ("type", "progress".into()),
+                ("id", (*id).into()),
+                ("depth", (*depth).into()),
+                ("best", notation(best)),
+                ("score", Json::Number(*score as f64)),
+                ("nodes", (*nodes).into()),
+                ("tt_hits", (*tt_hits).into()),
+                ("elapsed_ms", Json::Number(elapsed_ms.round())),
+                ("nps", Json::Number(nps.round())),
+                ("pv", pv.as_str().into()),
+            ],
+            Response::Done { id, depth, best } => vec![
+                ("type", "done".into()),
+                ("id", (*id).into()),
+                ("depth", (*depth).into()),
+                ("best", notation(best)),
+            ],
+            Response::Cancelled { id } => vec![("type", "cancelled".into()), ("id", (*id).into())],
+            Response::Error { id, message } => vec![
+                ("type", "error".into()),
+                ("id", (*id).into()),
+                ("message", message.as_str().into()),
+            ],
+        };
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value));
+        Json::Object(entries.collect())
+    }
+}
+
+struct Job {
+    id: usize,
+    forced: bool,
+    search: search::Incremental<State>,
+    time_ms: Option<usize>,
+    // when the first step ran
+    started: Option<f64>,
+}
+
+/// runs the searches requested by the page, at most `NODES_PER_STEP` positions per `step`.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct AiDispatcher {
+    jobs: VecDeque<Job>,
+}
+
+#[wasm_bindgen]
+impl AiDispatcher {
+    pub fn new() -> AiDispatcher {
+        AiDispatcher::default()
+    }
+    /// takes a request from the page, and returns the responses to post back as a JSON array.
+    pub fn receive(&mut self, message: &str) -> String {
+        let responses = match Request::parse(message) {
+            Ok(request) => self.handle(request),
+            Err(error) => {
+                // still answer to the right search if the id is readable
+                let json = Json::parse(message).ok();
+                let id = json.as_ref().and_then(|json| json.get("id")?.as_usize());
+                vec![Response::Error { id, message: error }]
+            }
+        };
+        to_json(&responses)
+    }
+    /// searches the current request until it finishes a depth, runs out of time or has searched
+    /// `NODES_PER_STEP` positions, whichever is first, looking every `NODES_PER_CHECK` positions.
+    /// returns the responses to post back as a JSON array, empty if it stopped partway through a depth with time left.
+    pub fn step(&mut self) -> String {
+        to_json(&self.advance())
+    }
+    /// true while there is a search to `step`.
+    pub fn is_busy(&self) -> bool {
+        !self.jobs.is_empty()
+    }
+}
+
+impl AiDispatcher {
+    pub fn handle(&mut self, request: Request) -> Vec<Response> {
+        match request {
+            Request::Search {
+                id,
+                board,
+                player,
+                strength,
+                time_ms,
+            } => {
+                let strength = strength.clamp(1, 10);
+                self.jobs.push_back(Job {
+                    id,
+                    forced: board.placeable(player).len() <= 1,
+                    search: ai::incremental(&board, player, ai::plies(strength), strength),
+                    time_ms,
+                    started: None,
+                });
+                vec![]
+            }
+            Request::Cancel { id } => {
+                let before = self.jobs.len();
+                self.jobs.retain(|job| job.id != id);
+                if self.jobs.len() < before {
+                    vec![Response::Cancelled { id }]
+                } else {
+                    vec![] // already done, or never asked
+                }
+            }
+        }
+    }
+
+    /// `step`, for Rust.
+    pub fn advance(&mut self) -> Vec<Response> {
+        let Some(job) = self.jobs.front_mut() else {
+            return vec![];
+        };
+        let started = *job.started.get_or_insert_with(now_ms);
+        let limit = job.time_ms;
+        let timed_out = || limit.is_some_and(|limit| now_ms() - started >= limit as f64);
+        let search = &mut job.search;
+        let depth = search.depth();
+        let mut searched = 0;
+        let mut out_of_time = false;
+        while !search.step(NODES_PER_CHECK) && search.depth() == depth {
+            // there is always a move to answer with, once the first ply is done
+            out_of_time = search.depth() > 0 && timed_out();
+            searched += NODES_PER_CHECK;
+            if out_of_time || searched >= NODES_PER_STEP {
+                break;
+            }
+        }
+        let mut responses = Vec::new();
+        let best = search.best().and_then(|(play, _)| play);
+        if let Some((_, score)) = search.best().filter(|_| search.depth() != depth) {
+            let report = search.report();
+            responses.push(Response::Progress {
+                id: job.id,
+                best,
+                score,
+                nps: report.nps(),
+                pv: report.pv_notation(),
+                depth: report.depth,
+                nodes: report.nodes,
+                tt_hits: report.tt_hits,
+                elapsed_ms: report.elapsed_ms,
+            });
+        }
+        // the game being over finishes the search too
+        let out_of_time = out_of_time || (search.depth() > 0 && timed_out());
+        if job.forced || out_of_time || search.is_finished() {
+            responses.push(Response::Done {
+                id: job.id,
+                depth: search.depth(),
+                best,
+            });
+            self.jobs.pop_front();
+        }
+        responses
+    }
+}
+
+fn to_json(responses: &[Response]) -> String {
+    Json::Array(responses.iter().map(Response::to_json).collect()).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    fn search(id: usize, strength: usize) -> String {
+        format!(
+            r#"{{"type":"search","id":{},"board":"........\n........\n........\n...bw...\n...wb...\n........\n........\n........","width":8,"height":8,"player":"b","strength":{}}}"#,
+            id, strength
+        )
+    }
+    #[test]
+    fn searches_deeper_each_step() {
+        let mut dispatcher = AiDispatcher::new();
+        assert_eq!(dispatcher.receive(&search(1, 4)), "[]");
+        let mut depths = Vec::new();
+        let (best, last) = loop {
+            let responses = dispatcher.advance();
+            match responses.as_slice() {
+                [Response::Progress { depth, .. }] => depths.push(*depth),
+                [last, Response::Done { id: 1, best, .. }] => break (*best, last.to_json()),
+                _ => panic!("{:?}", responses),
+            }
+        };
+        assert_eq!(depths, vec![1, 2]);
+        assert_eq!(last.get("depth").and_then(Json::as_usize), Some(3));
+        let pv = last.get("pv").and_then(Json::as_str).unwrap();
+        assert_eq!(pv.split(' ').count(), 3);
+        assert!(last.get("nodes").and_then(Json::as_usize).unwrap() > 0);
+        let board = Board::new(8, 8);
+        assert_eq!(best, ai::predict(&board, Piece::Black, 4, 4));
+        assert!(!dispatcher.is_busy());
+        assert_eq!(dispatcher.step(), "[]");
+    }
+    #[test]
+    fn cancel() {
+        let mut dispatcher = AiDispatcher::new();
+        dispatcher.receive(&search(1, 10));
+        dispatcher.receive(&search(2, 1));
+        dispatcher.step();
+        assert_eq!(
+            dispatcher.receive(r#"{"type":"cancel","id":1}"#),
+            r#"[{"type":"cancelled","id":1}]"#
+        );
+        // the next search is up
+        let responses = dispatcher.advance();
+        assert!(matches!(
+            responses.last(),
+            Some(Response::Done { id: 2, .. })
+        ));
+        assert_eq!(dispatcher.receive(r#"{"type":"cancel","id":2}"#), "[]");
+    }
+    #[test]
+    fn deep_iterations_are_split() {
+        let mut dispatcher = AiDispatcher::new();
+        dispatcher.receive(&search(1, 10));
+        // sooner or later a depth takes more than one step, which then answers nothing
+        let mut split = false;
+        while !split {
+            let before = dispatcher.jobs[0].search.nodes();
+            split = dispatcher.advance().is_empty();
+            let searched = dispatcher.jobs[0].search.nodes() - before;
+            assert!(searched <= NODES_PER_STEP + NODES_PER_CHECK, "{}", searched);
+        }
+        assert_eq!(
+            dispatcher.receive(r#"{"type":"cancel","id":1}"#),
+            r#"[{"type":"cancelled","id":1}]"#
+        );
+    }
+    #[test]
+    fn rules_in_requests() {
+        let request = search(1, 3).replace(
+            "\"strength\"",
+            "\"win_condition\":\"fewest_discs\",\"topology\":\"torus\",\"strength\"",
+        );
+        let Ok(Request::Search { board, .. }) = Request::parse(&request) else {
+            panic!("{}", request);
+        };
+        assert_eq!(board.win_condition, WinCondition::FewestDiscs);
+        assert_eq!(board.topology, Topology::Torus);
+        let Ok(Request::Search { board, .. }) = Request::parse(&search(1, 3)) else {
+            panic!();
+        };
+        assert_eq!(board.win_condition, WinCondition::MostDiscs);
+        assert!(Request::parse(
+            &search(1, 3).replace("\"strength\"", "\"topology\":\"cube\",\"strength\"")
+        )
+        .is_err());
+    }
+    #[test]
+    fn time_limit() {
+        let mut dispatcher = AiDispatcher::new();
+        let request = search(1, 10).replace("\"strength\"", "\"time_ms\":0,\"strength\"");
+        dispatcher.receive(&request);
+        let responses = dispatcher.advance();
+        assert!(matches!(
+            responses.as_slice(),
+            [
+                Response::Progress { depth: 1, .. },
+                Response::Done { depth: 1, .. }
+            ]
+        ));
+    }
+    #[test]
+    fn bad_requests() {
+        let mut dispatcher = AiDispatcher::new();
+        let error = |id: &str, message: &str| {
+            format!(
+                r#"[{{"type":"error","id":{},"message":"{}"}}]"#,
+                id, message
+            )
+        };
+        assert_eq!(
+            dispatcher.receive(r#"{"type":"cancel"}"#),
+            error("null", "missing or bad id")
+        );
+        let request = search(1, 3).replace("\"b\",", "\"x\",");
+        assert_eq!(
+            dispatcher.receive(&request),
+            error("1", r#"player should be \"b\" or \"w\""#)
+        );
+        assert!(dispatcher.receive("{").contains("UnexpectedEnd"));
+        assert!(!dispatcher.is_busy());
+    }
+}
@@ -1,4 +1,10 @@
+import type { Topology, WinCondition } from "boardgame-ai";
+
 export type Config = {
-  board_size: number;
+  board_width: number;
+  board_height: number;
   strength: number;
+  win_condition: WinCondition;
+  topology: Topology;
+  gravity: boolean;
 };